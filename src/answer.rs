@@ -0,0 +1,101 @@
+use std::fmt::Display;
+
+use crate::json;
+
+/// What solving one part of a puzzle gives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// A picture to read the answer from, one string per row.
+    Grid(Vec<String>),
+    /// The part has nothing to solve, or the input has no solution.
+    NoAnswer,
+}
+
+impl Answer {
+    /// The answer as a JSON value: a number, a string, an array of rows or `null`.
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Int(number) => number.to_string(),
+            Answer::Text(text) => json::string(text),
+            Answer::Grid(rows) => format!(
+                "[{}]",
+                rows.iter().map(|row| json::string(row)).collect::<Vec<String>>().join(", ")
+            ),
+            Answer::NoAnswer => String::from("null"),
+        }
+    }
+}
+
+/// Renders the answer as it is printed and compared against expected answers.
+/// Grids put every row on its own line.
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::NoAnswer => write!(f, "no answer"),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($int:ty),*) => {
+        $(impl From<$int> for Answer {
+            fn from(number: $int) -> Answer {
+                Answer::Int(number as i64)
+            }
+        })*
+    };
+}
+
+from_int!(i32, i64, u32);
+
+impl From<u64> for Answer {
+    fn from(number: u64) -> Answer {
+        Answer::Int(i64::try_from(number).expect("answer does not fit in an i64"))
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(number: usize) -> Answer {
+        Answer::Int(i64::try_from(number).expect("answer does not fit in an i64"))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Answer {
+        answer.map_or(Answer::NoAnswer, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+
+    #[test]
+    fn rendering() {
+        let grid = Answer::Grid(vec![String::from("#."), String::from(".#")]);
+        assert_eq!(grid.to_string(), "#.\n.#");
+        assert_eq!(grid.to_json(), "[\"#.\", \".#\"]");
+        assert_eq!(Answer::from(42u64).to_json(), "42");
+        assert_eq!(Answer::from("a\"b").to_json(), "\"a\\\"b\"");
+        assert_eq!(Answer::from(None::<i32>), Answer::NoAnswer);
+        assert_eq!(Answer::NoAnswer.to_string(), "no answer");
+        assert_eq!(Answer::NoAnswer.to_json(), "null");
+    }
+}
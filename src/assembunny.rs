@@ -0,0 +1,312 @@
+use std::fmt::Display;
+
+use crate::{
+    error::{self, PuzzleError},
+    params::{Kind, Param, Params},
+};
+
+use self::{compiler::Compiled, optimizer::Op};
+
+pub mod compiler;
+pub mod debugger;
+pub mod decompiler;
+pub mod optimizer;
+
+pub const REGISTER_NAMES: [char; 4] = ['a', 'b', 'c', 'd'];
+
+pub type Registers = [i64; 4];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+impl Operand {
+    fn parse(word: Option<&str>) -> Result<Operand, String> {
+        let word = word.ok_or("missing operand")?;
+        if let Some(register) = REGISTER_NAMES.iter().position(|name| word == name.to_string()) {
+            return Ok(Operand::Register(register));
+        }
+        word.parse::<i64>()
+            .map(Operand::Value)
+            .map_err(|_| format!("expected a register a-d or a number, got '{}'", word))
+    }
+
+    fn parse_register(word: Option<&str>) -> Result<Operand, String> {
+        match Operand::parse(word)? {
+            Operand::Value(value) => Err(format!("expected a register a-d, got '{}'", value)),
+            register => Ok(register),
+        }
+    }
+
+    pub fn value(self, registers: &Registers) -> i64 {
+        match self {
+            Operand::Register(register) => registers[register],
+            Operand::Value(value) => value,
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", REGISTER_NAMES[*register]),
+            Operand::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// One assembunny instruction. Operands that must name a register are only
+/// checked when parsing, because `tgl` can turn them into invalid instructions
+/// that are skipped when executed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Cpy(Operand, Operand),
+    Inc(Operand),
+    Dec(Operand),
+    Jnz(Operand, Operand),
+    Tgl(Operand),
+    Out(Operand),
+}
+
+impl Instruction {
+    pub fn parse(line: &str) -> Result<Instruction, String> {
+        let mut words = line.split_whitespace();
+        let instruction = match words.next() {
+            Some("cpy") => Instruction::Cpy(Operand::parse(words.next())?, Operand::parse_register(words.next())?),
+            Some("inc") => Instruction::Inc(Operand::parse_register(words.next())?),
+            Some("dec") => Instruction::Dec(Operand::parse_register(words.next())?),
+            Some("jnz") => Instruction::Jnz(Operand::parse(words.next())?, Operand::parse(words.next())?),
+            Some("tgl") => Instruction::Tgl(Operand::parse(words.next())?),
+            Some("out") => Instruction::Out(Operand::parse(words.next())?),
+            Some(other) => return Err(format!("unknown instruction '{}'", other)),
+            None => return Err(String::from("empty line")),
+        };
+        match words.next() {
+            Some(extra) => Err(format!("unexpected operand '{}'", extra)),
+            None => Ok(instruction),
+        }
+    }
+
+    /// The instruction `tgl` turns this one into.
+    pub fn toggled(self) -> Instruction {
+        match self {
+            Instruction::Inc(x) => Instruction::Dec(x),
+            Instruction::Dec(x) | Instruction::Tgl(x) | Instruction::Out(x) => Instruction::Inc(x),
+            Instruction::Jnz(x, y) => Instruction::Cpy(x, y),
+            Instruction::Cpy(x, y) => Instruction::Jnz(x, y),
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Cpy(x, y) => write!(f, "cpy {} {}", x, y),
+            Instruction::Inc(x) => write!(f, "inc {}", x),
+            Instruction::Dec(x) => write!(f, "dec {}", x),
+            Instruction::Jnz(x, y) => write!(f, "jnz {} {}", x, y),
+            Instruction::Tgl(x) => write!(f, "tgl {}", x),
+            Instruction::Out(x) => write!(f, "out {}", x),
+        }
+    }
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, PuzzleError> {
+    error::parse_lines(input.lines(), Instruction::parse)
+}
+
+pub struct Computer {
+    pub program: Vec<Instruction>,
+    pub registers: Registers,
+    pub pc: i64,
+    pub output: Vec<i64>,
+    ops: Option<Vec<Op>>,
+}
+
+impl Computer {
+    pub fn new(program: Vec<Instruction>, registers: Registers) -> Computer {
+        Computer {
+            program,
+            registers,
+            pc: 0,
+            output: vec![],
+            ops: None,
+        }
+    }
+
+    /// Lets the computer run the loops the optimizer recognizes at once.
+    pub fn optimized(mut self) -> Computer {
+        self.ops = Some(optimizer::optimize(&self.program));
+        self
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc < 0 || self.pc >= self.program.len() as i64
+    }
+
+    /// Runs until the program counter leaves the program.
+    pub fn run(&mut self) {
+        while !self.is_halted() {
+            self.step();
+        }
+    }
+
+    /// Runs until the next `out` instruction and returns its value, or `None` if
+    /// the program halts or `step_limit` instructions pass without output.
+    pub fn next_output(&mut self, step_limit: u64) -> Option<i64> {
+        let produced = self.output.len();
+        for _ in 0..step_limit {
+            if self.is_halted() {
+                return None;
+            }
+            self.step();
+            if self.output.len() > produced {
+                return self.output.last().copied();
+            }
+        }
+        None
+    }
+
+    /// Executes the instruction at the program counter, or a whole optimized
+    /// loop starting there at once. Returns whether a loop ran.
+    pub fn step(&mut self) -> bool {
+        if let Some(Op::Loop(found)) = self.ops.as_ref().map(|ops| &ops[self.pc as usize]) {
+            if found.apply(&mut self.registers) {
+                self.pc += found.len as i64;
+                return true;
+            }
+        }
+        let mut offset = 1;
+        match self.program[self.pc as usize] {
+            Instruction::Cpy(x, Operand::Register(y)) => self.registers[y] = x.value(&self.registers),
+            Instruction::Inc(Operand::Register(x)) => self.registers[x] += 1,
+            Instruction::Dec(Operand::Register(x)) => self.registers[x] -= 1,
+            Instruction::Jnz(x, y) => {
+                if x.value(&self.registers) != 0 {
+                    offset = y.value(&self.registers);
+                }
+            }
+            Instruction::Tgl(x) => {
+                let target = self.pc + x.value(&self.registers);
+                if let Some(instruction) = usize::try_from(target).ok().and_then(|i| self.program.get_mut(i)) {
+                    *instruction = instruction.toggled();
+                    // The toggled instruction may break a loop or complete a new one.
+                    if let Some(ops) = &mut self.ops {
+                        *ops = optimizer::optimize(&self.program);
+                    }
+                }
+            }
+            Instruction::Out(x) => self.output.push(x.value(&self.registers)),
+            Instruction::Cpy(_, Operand::Value(_))
+            | Instruction::Inc(Operand::Value(_))
+            | Instruction::Dec(Operand::Value(_)) => (),
+        }
+        self.pc += offset;
+        false
+    }
+}
+
+/// How days 12 and 23 execute their program.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Engine {
+    Plain,
+    Optimized,
+    /// Runs the program compiled to register-indexed code, without the optimizer.
+    Compiled,
+    /// Runs the program both optimized and plain and checks they end the same.
+    Verify,
+}
+
+impl Engine {
+    pub const NAMES: [&'static str; 4] = ["optimized", "compiled", "plain", "verify"];
+
+    pub fn from_params(params: &Params) -> Engine {
+        match params.text("engine") {
+            "plain" => Engine::Plain,
+            "compiled" => Engine::Compiled,
+            "verify" => Engine::Verify,
+            _ => Engine::Optimized,
+        }
+    }
+}
+
+/// The `engine` parameter of the puzzles that run assembunny programs.
+pub fn engine_param() -> Param {
+    Param::new(
+        "engine",
+        Kind::Choice(&Engine::NAMES),
+        ["optimized", "optimized"],
+        "run the program optimized, compiled, plain, or both optimized and plain to verify the optimizer",
+    )
+}
+
+/// Runs `program` to completion and returns the final registers.
+pub fn run_program(program: Vec<Instruction>, registers: Registers, engine: Engine) -> Result<Registers, PuzzleError> {
+    match engine {
+        Engine::Plain => {
+            let mut computer = Computer::new(program, registers);
+            computer.run();
+            Ok(computer.registers)
+        }
+        Engine::Optimized => {
+            let mut computer = Computer::new(program, registers).optimized();
+            computer.run();
+            Ok(computer.registers)
+        }
+        Engine::Compiled => {
+            let mut registers = registers;
+            Compiled::new(program).run(&mut registers);
+            Ok(registers)
+        }
+        Engine::Verify => {
+            let mut plain = Computer::new(program.clone(), registers);
+            let mut optimized = Computer::new(program, registers).optimized();
+            plain.run();
+            optimized.run();
+            if plain.registers != optimized.registers || plain.program != optimized.program {
+                return Err(PuzzleError::new(format!(
+                    "the optimized run ended with registers {:?}, the plain run with {:?}",
+                    optimized.registers, plain.registers
+                )));
+            }
+            Ok(optimized.registers)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_program, run_program, Computer, Engine, Instruction, Operand};
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Instruction::parse("cpy 41 a"),
+            Ok(Instruction::Cpy(Operand::Value(41), Operand::Register(0)))
+        );
+        assert_eq!(Instruction::parse("jnz d -2").unwrap().to_string(), "jnz d -2");
+        assert!(Instruction::parse("cpy a 1").is_err());
+        assert!(Instruction::parse("inc e").is_err());
+        assert!(Instruction::parse("mul a b").is_err());
+        assert_eq!(parse_program("inc a\ndec").unwrap_err().line, Some(2));
+    }
+
+    #[test]
+    fn loops_match_plain_execution() {
+        let program = parse_program("cpy 3 b\ncpy 4 d\ncpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5\nout a").unwrap();
+        let mut computer = Computer::new(program.clone(), [5, 0, 0, 0]).optimized();
+        computer.run();
+        assert_eq!(computer.registers, [17, 3, 0, 0]);
+        assert_eq!(computer.output, vec![17]);
+        assert_eq!(run_program(program, [5, 0, 0, 0], Engine::Verify), Ok([17, 3, 0, 0]));
+    }
+
+    #[test]
+    fn toggled_loops_are_reoptimized() {
+        // The `tgl` turns `inc d` into `dec d`, which makes the loop below it count down.
+        let program = parse_program("cpy 3 c\ncpy 4 d\ntgl c\njnz 0 0\ninc a\ninc d\njnz d -2").unwrap();
+        assert_eq!(run_program(program, [0; 4], Engine::Verify), Ok([4, 0, 3, 0]));
+    }
+}
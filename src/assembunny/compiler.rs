@@ -0,0 +1,130 @@
+use super::{Instruction, Operand, Registers};
+
+/// An instruction with its operands resolved at compile time, so running it
+/// needs no operand matching and constant jumps know their target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Code {
+    CopyValue(i64, usize),
+    CopyRegister(usize, usize),
+    Inc(usize),
+    Dec(usize),
+    /// Jumps to an absolute position if the register is not zero.
+    JumpIfNotZero(usize, i64),
+    /// Jumps to an absolute position.
+    Jump(i64),
+    /// A jump whose offset is read from a register.
+    Jnz(Operand, Operand),
+    Tgl(Operand),
+    Out(Operand),
+    /// Instructions that are invalid after a `tgl`, and jumps that never happen.
+    Skip,
+}
+
+fn compile_one(instruction: Instruction, pc: i64) -> Code {
+    match instruction {
+        Instruction::Cpy(Operand::Value(x), Operand::Register(y)) => Code::CopyValue(x, y),
+        Instruction::Cpy(Operand::Register(x), Operand::Register(y)) => Code::CopyRegister(x, y),
+        Instruction::Inc(Operand::Register(x)) => Code::Inc(x),
+        Instruction::Dec(Operand::Register(x)) => Code::Dec(x),
+        Instruction::Jnz(Operand::Value(0), _) => Code::Skip,
+        Instruction::Jnz(Operand::Value(_), Operand::Value(offset)) => Code::Jump(pc + offset),
+        Instruction::Jnz(Operand::Register(x), Operand::Value(offset)) => Code::JumpIfNotZero(x, pc + offset),
+        Instruction::Jnz(x, y) => Code::Jnz(x, y),
+        Instruction::Tgl(x) => Code::Tgl(x),
+        Instruction::Out(x) => Code::Out(x),
+        Instruction::Cpy(_, Operand::Value(_))
+        | Instruction::Inc(Operand::Value(_))
+        | Instruction::Dec(Operand::Value(_)) => Code::Skip,
+    }
+}
+
+/// A program compiled to register-indexed code. A `tgl` recompiles only the
+/// instruction it changes.
+pub struct Compiled {
+    pub program: Vec<Instruction>,
+    pub output: Vec<i64>,
+    code: Vec<Code>,
+}
+
+impl Compiled {
+    pub fn new(program: Vec<Instruction>) -> Compiled {
+        let code = program
+            .iter()
+            .enumerate()
+            .map(|(pc, instruction)| compile_one(*instruction, pc as i64))
+            .collect();
+        Compiled {
+            program,
+            output: vec![],
+            code,
+        }
+    }
+
+    /// Runs from the first instruction until the program counter leaves the program.
+    pub fn run(&mut self, registers: &mut Registers) {
+        let mut pc: i64 = 0;
+        let len = self.code.len() as i64;
+        // A negative pc turns into a huge index, so `get` also ends the program there.
+        while let Some(code) = self.code.get(pc as usize) {
+            match *code {
+                Code::CopyValue(x, y) => registers[y] = x,
+                Code::CopyRegister(x, y) => registers[y] = registers[x],
+                Code::Inc(x) => registers[x] += 1,
+                Code::Dec(x) => registers[x] -= 1,
+                Code::JumpIfNotZero(x, target) => {
+                    if registers[x] != 0 {
+                        pc = target;
+                        continue;
+                    }
+                }
+                Code::Jump(target) => {
+                    pc = target;
+                    continue;
+                }
+                Code::Jnz(x, y) => {
+                    if x.value(registers) != 0 {
+                        pc += y.value(registers);
+                        continue;
+                    }
+                }
+                Code::Tgl(x) => {
+                    let target = pc + x.value(registers);
+                    if (0..len).contains(&target) {
+                        let instruction = self.program[target as usize].toggled();
+                        self.program[target as usize] = instruction;
+                        self.code[target as usize] = compile_one(instruction, target);
+                    }
+                }
+                Code::Out(x) => self.output.push(x.value(registers)),
+                Code::Skip => (),
+            }
+            pc += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Compiled;
+    use crate::assembunny::{parse_program, Computer};
+
+    #[test]
+    fn matches_plain_execution() {
+        let programs = [
+            "cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a",
+            "cpy 3 b\ncpy b c\ninc a\ndec c\njnz c -2\ndec b\njnz b -5\nout a\ncpy 2 d\njnz 1 d\nout b",
+            "cpy 5 c\ntgl c\ncpy 2 d\njnz 1 d\ninc b\ncpy 1 a\njnz 2 b\ninc a",
+        ];
+        for text in programs {
+            let program = parse_program(text).unwrap();
+            let mut plain = Computer::new(program.clone(), [1, 0, 0, 0]);
+            plain.run();
+            let mut compiled = Compiled::new(program);
+            let mut registers = [1, 0, 0, 0];
+            compiled.run(&mut registers);
+            assert_eq!(registers, plain.registers, "{}", text);
+            assert_eq!(compiled.program, plain.program, "{}", text);
+            assert_eq!(compiled.output, plain.output, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::fmt::Display;
+
+use super::{Computer, Instruction, Registers, REGISTER_NAMES};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// Longer symbols first, so `<=` is not read as `<`.
+    const SYMBOLS: [(&'static str, Comparison); 7] = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("=", Comparison::Equal),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn holds(self, left: i64, right: i64) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// A breakpoint, which fires on a step that makes it hold.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Condition {
+    /// The next instruction to run is at this position.
+    Pc(i64),
+    Register(usize, Comparison, i64),
+}
+
+impl Condition {
+    /// Parses `pc=N`, or a register compared to a number such as `a>=10` or `c!=0`.
+    pub fn parse(text: &str) -> Result<Condition, String> {
+        let (start, symbol, comparison) = Comparison::SYMBOLS
+            .iter()
+            .filter_map(|(symbol, comparison)| text.find(symbol).map(|start| (start, *symbol, *comparison)))
+            .min_by_key(|(start, symbol, _)| (*start, usize::MAX - symbol.len()))
+            .ok_or_else(|| format!("expected a condition like pc=4 or a>=10, got '{}'", text))?;
+        let name = text[..start].trim();
+        let value = text[start + symbol.len()..].trim();
+        let value = value
+            .parse::<i64>()
+            .map_err(|_| format!("expected a number after '{}', got '{}'", symbol, value))?;
+        if name == "pc" {
+            return match comparison {
+                Comparison::Equal => Ok(Condition::Pc(value)),
+                _ => Err(format!("the pc can only be compared with '=', got '{}'", text)),
+            };
+        }
+        match REGISTER_NAMES.iter().position(|register| name == register.to_string()) {
+            Some(register) => Ok(Condition::Register(register, comparison, value)),
+            None => Err(format!("expected pc or a register a-d, got '{}'", name)),
+        }
+    }
+
+    pub fn holds(&self, computer: &Computer) -> bool {
+        match *self {
+            Condition::Pc(pc) => computer.pc == pc,
+            Condition::Register(register, comparison, value) => {
+                comparison.holds(computer.registers[register], value)
+            }
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::Pc(pc) => write!(f, "pc == {}", pc),
+            Condition::Register(register, comparison, value) => {
+                write!(f, "{} {} {}", REGISTER_NAMES[*register], comparison, value)
+            }
+        }
+    }
+}
+
+/// What one step did to the machine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    /// 1-based number of the step.
+    pub number: u64,
+    pub pc: i64,
+    pub instruction: Instruction,
+    /// Whether a whole optimized loop ran in this step.
+    pub collapsed: bool,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<i64>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let instruction = match self.collapsed {
+            true => format!("{} (loop)", self.instruction),
+            false => self.instruction.to_string(),
+        };
+        write!(f, "{:>10}  {:>3}: {:<18}", self.number, self.pc, instruction)?;
+        for (register, (before, after)) in self.before.iter().zip(self.after).enumerate() {
+            if *before != after {
+                write!(f, " {}: {} -> {}", REGISTER_NAMES[register], before, after)?;
+            }
+        }
+        match self.output {
+            Some(value) => write!(f, " out: {}", value),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why [`Debugger::run`] returned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(Condition),
+    Limit,
+}
+
+/// Runs a [`Computer`] one step at a time, counting how often every
+/// instruction runs and stopping at breakpoints.
+pub struct Debugger {
+    pub computer: Computer,
+    pub steps: u64,
+    /// Executions of every program position. A loop run at once counts once.
+    pub histogram: Vec<u64>,
+    breakpoints: Vec<Condition>,
+}
+
+impl Debugger {
+    pub fn new(computer: Computer, breakpoints: Vec<Condition>) -> Debugger {
+        let histogram = vec![0; computer.program.len()];
+        Debugger {
+            computer,
+            steps: 0,
+            histogram,
+            breakpoints,
+        }
+    }
+
+    /// Executes one step. The computer must not be halted.
+    pub fn step(&mut self) -> Step {
+        let pc = self.computer.pc;
+        let instruction = self.computer.program[pc as usize];
+        let before = self.computer.registers;
+        let produced = self.computer.output.len();
+        let collapsed = self.computer.step();
+        self.steps += 1;
+        self.histogram[pc as usize] += 1;
+        Step {
+            number: self.steps,
+            pc,
+            instruction,
+            collapsed,
+            before,
+            after: self.computer.registers,
+            output: self.computer.output.get(produced).copied(),
+        }
+    }
+
+    /// Runs until the program halts, a step makes a breakpoint hold that did not
+    /// hold before it, or `limit` steps have run in total. Every step is passed
+    /// to `on_step`.
+    pub fn run(&mut self, limit: Option<u64>, mut on_step: impl FnMut(&Step)) -> Stop {
+        loop {
+            if self.computer.is_halted() {
+                return Stop::Halted;
+            }
+            if limit.is_some_and(|limit| self.steps >= limit) {
+                return Stop::Limit;
+            }
+            let held = self.holding();
+            let step = self.step();
+            on_step(&step);
+            let fired = self.holding().into_iter().zip(held).position(|(holds, held)| holds && !held);
+            if let Some(i) = fired {
+                return Stop::Breakpoint(self.breakpoints[i]);
+            }
+        }
+    }
+
+    fn holding(&self) -> Vec<bool> {
+        self.breakpoints.iter().map(|condition| condition.holds(&self.computer)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comparison, Condition, Debugger, Stop};
+    use crate::assembunny::{parse_program, Computer};
+
+    #[test]
+    fn conditions() {
+        assert_eq!(Condition::parse("pc=4"), Ok(Condition::Pc(4)));
+        assert_eq!(
+            Condition::parse("a>=10"),
+            Ok(Condition::Register(0, Comparison::GreaterOrEqual, 10))
+        );
+        assert_eq!(Condition::parse("d != -1"), Ok(Condition::Register(3, Comparison::NotEqual, -1)));
+        assert!(Condition::parse("pc>4").is_err());
+        assert!(Condition::parse("e=1").is_err());
+        assert!(Condition::parse("a").is_err());
+    }
+
+    #[test]
+    fn breakpoints_and_histogram() {
+        let program = parse_program("cpy 3 c\ninc a\ndec c\njnz c -2\nout a").unwrap();
+        let breakpoints = vec![Condition::parse("a=2").unwrap()];
+        let mut debugger = Debugger::new(Computer::new(program.clone(), [0; 4]), breakpoints);
+        let mut steps = vec![];
+        assert_eq!(
+            debugger.run(None, |step| steps.push(step.to_string())),
+            Stop::Breakpoint(Condition::Register(0, Comparison::Equal, 2))
+        );
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[1].trim_end(), "         2    1: inc a              a: 0 -> 1");
+        assert_eq!(debugger.run(Some(7), |_| ()), Stop::Limit);
+        assert_eq!(debugger.run(None, |_| ()), Stop::Halted);
+        assert_eq!(debugger.histogram, vec![1, 3, 3, 3, 1]);
+        assert_eq!(debugger.computer.output, vec![3]);
+
+        let mut optimized = Debugger::new(Computer::new(program, [0; 4]).optimized(), vec![]);
+        let mut collapsed = vec![];
+        assert_eq!(optimized.run(None, |step| collapsed.push(step.collapsed)), Stop::Halted);
+        assert_eq!(collapsed, vec![false, true, false]);
+        assert_eq!(optimized.histogram, vec![1, 1, 0, 0, 1]);
+    }
+}
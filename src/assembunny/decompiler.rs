@@ -0,0 +1,416 @@
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    ops::{Add, Mul},
+};
+
+use super::{
+    optimizer::{self, Loop, Op},
+    Instruction, Operand, REGISTER_NAMES,
+};
+
+/// A value computed by straight-line code, in terms of the registers before it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Const(i64),
+    Register(usize),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+/// Adds two expressions, folding constants so they end up on the right.
+impl Add for Expr {
+    type Output = Expr;
+
+    fn add(self, other: Expr) -> Expr {
+        match (self, other) {
+            (Expr::Const(x), Expr::Const(y)) => Expr::Const(x + y),
+            (x, y) if x == y => Expr::Const(2) * x,
+            (e, Expr::Const(0)) | (Expr::Const(0), e) => e,
+            (Expr::Const(x), e) => e + Expr::Const(x),
+            (Expr::Add(e, k), Expr::Const(y)) => match *k {
+                Expr::Const(x) => *e + Expr::Const(x + y),
+                k => Expr::Add(Box::new(Expr::Add(e, Box::new(k))), Box::new(Expr::Const(y))),
+            },
+            (x, y) => Expr::Add(Box::new(x), Box::new(y)),
+        }
+    }
+}
+
+/// Multiplies two expressions, folding constants so they end up on the left.
+impl Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, other: Expr) -> Expr {
+        match (self, other) {
+            (Expr::Const(x), Expr::Const(y)) => Expr::Const(x * y),
+            (Expr::Const(0), _) | (_, Expr::Const(0)) => Expr::Const(0),
+            (Expr::Const(1), e) | (e, Expr::Const(1)) => e,
+            (e, Expr::Const(k)) => Expr::Const(k) * e,
+            (x, y) => Expr::Mul(Box::new(x), Box::new(y)),
+        }
+    }
+}
+
+impl Expr {
+    fn reads(&self, register: usize) -> bool {
+        match self {
+            Expr::Const(_) => false,
+            Expr::Register(r) => *r == register,
+            Expr::Add(x, y) | Expr::Mul(x, y) => x.reads(register) || y.reads(register),
+        }
+    }
+
+    fn is_positive(&self) -> bool {
+        matches!(self, Expr::Const(value) if *value > 0)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Const(value) => write!(f, "{}", value),
+            Expr::Register(register) => write!(f, "{}", REGISTER_NAMES[*register]),
+            Expr::Add(x, y) => match y.as_ref() {
+                Expr::Const(value) if *value < 0 => write!(f, "{} - {}", x, -value),
+                Expr::Mul(k, e) if **k == Expr::Const(-1) => write!(f, "{} - {}", x, Factor(e)),
+                _ => write!(f, "{} + {}", x, y),
+            },
+            Expr::Mul(x, y) if **x == Expr::Const(-1) => write!(f, "-{}", Factor(y)),
+            Expr::Mul(x, y) => write!(f, "{} * {}", Factor(x), Factor(y)),
+        }
+    }
+}
+
+/// Shows a sum in parentheses, for use as a factor.
+struct Factor<'a>(&'a Expr);
+
+impl Display for Factor<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Expr::Add(..) => write!(f, "({})", self.0),
+            e => write!(f, "{}", e),
+        }
+    }
+}
+
+/// The effect of the straight-line code since `start`.
+struct Group {
+    start: usize,
+    values: [Expr; 4],
+    /// Loop counters that must be positive for the folded loops to run as shown.
+    assumptions: Vec<Expr>,
+}
+
+impl Group {
+    fn new(start: usize) -> Group {
+        Group {
+            start,
+            values: [0, 1, 2, 3].map(Expr::Register),
+            assumptions: vec![],
+        }
+    }
+
+    fn value(&self, operand: Operand) -> Expr {
+        match operand {
+            Operand::Register(register) => self.values[register].clone(),
+            Operand::Value(value) => Expr::Const(value),
+        }
+    }
+
+    fn assume_positive(&mut self, e: Expr) {
+        if !e.is_positive() && !self.assumptions.contains(&e) {
+            self.assumptions.push(e);
+        }
+    }
+
+    /// Folds a loop the optimizer recognized into the register values, the same way
+    /// [`Loop::apply`] runs it.
+    fn apply(&mut self, found: &Loop) {
+        let times = self.values[found.counter].clone();
+        self.assume_positive(times.clone());
+        let mut change = found.deltas.map(Expr::Const);
+        if let Some(inner) = &found.inner {
+            let repeat = self.value(inner.source);
+            self.assume_positive(repeat.clone());
+            for (change, delta) in change.iter_mut().zip(inner.deltas) {
+                *change = change.clone() + Expr::Const(delta) * repeat.clone();
+            }
+        }
+        let old = self.values.clone();
+        for (register, change) in change.into_iter().enumerate() {
+            self.values[register] = old[register].clone() + change * times.clone();
+        }
+        self.values[found.counter] = Expr::Const(0);
+        if let Some(inner) = &found.inner {
+            self.values[inner.counter] = Expr::Const(0);
+        }
+    }
+}
+
+struct Decompiler<'a> {
+    program: &'a [Instruction],
+    ops: Vec<Op>,
+    targets: HashSet<usize>,
+    /// Targets of jumps by a register whose value is known where it jumps.
+    computed: HashSet<usize>,
+    /// Start and the position after every loop around the code being decompiled.
+    loops: Vec<(usize, usize)>,
+    lines: Vec<String>,
+}
+
+impl Decompiler<'_> {
+    fn emit(&mut self, pc: Option<usize>, depth: usize, text: &str) {
+        let pc = pc.map(|pc| pc.to_string()).unwrap_or_default();
+        self.lines.push(format!("{:>4} | {}{}", pc, "    ".repeat(depth), text));
+    }
+
+    /// Writes out the assignments of `group` so that no register is overwritten
+    /// before every other assignment has read it, and starts a new group at `next`.
+    fn flush(&mut self, group: &mut Group, depth: usize, next: usize) {
+        let mut pending = (0..4)
+            .filter(|register| group.values[*register] != Expr::Register(*register))
+            .collect::<Vec<usize>>();
+        let mut pc = Some(group.start);
+        if !group.assumptions.is_empty() {
+            let assumptions = group.assumptions.iter().map(|e| format!("{} > 0", e)).collect::<Vec<String>>();
+            self.emit(pc.take(), depth, &format!("// assuming {}", assumptions.join(" and ")));
+        }
+        while !pending.is_empty() {
+            let free = pending.iter().position(|register| {
+                pending
+                    .iter()
+                    .all(|other| other == register || !group.values[*other].reads(*register))
+            });
+            match free {
+                Some(i) => {
+                    let register = pending.remove(i);
+                    let text = format!("{} = {}", REGISTER_NAMES[register], group.values[register]);
+                    self.emit(pc.take(), depth, &text);
+                }
+                None => {
+                    // The remaining assignments read each other, so they happen at once.
+                    let names = pending.iter().map(|r| REGISTER_NAMES[*r].to_string()).collect::<Vec<String>>();
+                    let values = pending.iter().map(|r| group.values[*r].to_string()).collect::<Vec<String>>();
+                    let text = format!("({}) = ({})", names.join(", "), values.join(", "));
+                    self.emit(pc.take(), depth, &text);
+                    pending.clear();
+                }
+            }
+        }
+        *group = Group::new(next);
+    }
+
+    /// Describes a jump to `target`, using `break` and `continue` for the innermost loop.
+    fn jump(&self, target: i64) -> String {
+        match self.loops.last() {
+            Some((_, after)) if *after as i64 == target => String::from("break"),
+            Some((start, _)) if *start as i64 == target => String::from("continue"),
+            _ => format!("goto {}", target),
+        }
+    }
+
+    fn back_edge(&self, end: usize) -> Option<usize> {
+        match self.program[end] {
+            Instruction::Jnz(Operand::Value(0), _) => None,
+            Instruction::Jnz(_, Operand::Value(offset)) if offset < 0 => {
+                end.checked_sub(offset.unsigned_abs() as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn block(&mut self, start: usize, end: usize, depth: usize) {
+        let mut group = Group::new(start);
+        let mut i = start;
+        while i < end {
+            if i != group.start && self.targets.contains(&i) {
+                self.flush(&mut group, depth, i);
+            }
+            if let Op::Loop(found) = &self.ops[i] {
+                if i + found.len <= end {
+                    let found = found.clone();
+                    group.apply(&found);
+                    i += found.len;
+                    continue;
+                }
+            }
+            if let Some(last) = (i + 1..end).rev().find(|j| self.back_edge(*j) == Some(i)) {
+                self.flush(&mut group, depth, i);
+                let (open, close) = match self.program[last] {
+                    Instruction::Jnz(Operand::Register(x), _) => {
+                        (String::from("do {"), format!("}} while {} != 0", REGISTER_NAMES[x]))
+                    }
+                    _ => (String::from("loop {"), String::from("}")),
+                };
+                self.emit(Some(i), depth, &open);
+                self.loops.push((i, last + 1));
+                self.block(i, last, depth + 1);
+                self.loops.pop();
+                self.emit(None, depth, &close);
+                i = last + 1;
+                group = Group::new(i);
+                continue;
+            }
+            let instruction = self.program[i];
+            match instruction {
+                Instruction::Cpy(x, Operand::Register(y)) => group.values[y] = group.value(x),
+                Instruction::Inc(Operand::Register(x)) => group.values[x] = group.values[x].clone() + Expr::Const(1),
+                Instruction::Dec(Operand::Register(x)) => group.values[x] = group.values[x].clone() + Expr::Const(-1),
+                Instruction::Cpy(_, Operand::Value(_))
+                | Instruction::Inc(Operand::Value(_))
+                | Instruction::Dec(Operand::Value(_))
+                | Instruction::Jnz(Operand::Value(0), _) => (),
+                Instruction::Jnz(x, y) => {
+                    let offset = group.value(y);
+                    self.flush(&mut group, depth, i + 1);
+                    let target = match offset {
+                        Expr::Const(offset) => Some(i as i64 + offset),
+                        _ => None,
+                    };
+                    if let (Operand::Register(_), Some(target)) = (y, target) {
+                        self.computed.extend(usize::try_from(target));
+                    }
+                    let next = match (x, target) {
+                        (Operand::Register(x), Some(target)) if target > i as i64 && target <= end as i64 => {
+                            Some(self.branch(x, i, target as usize, end, depth))
+                        }
+                        _ => None,
+                    };
+                    if let Some(next) = next {
+                        i = next;
+                        group = Group::new(i);
+                        continue;
+                    }
+                    let jump = match target {
+                        Some(target) => self.jump(target),
+                        None => format!("goto {} + {}", i, y),
+                    };
+                    let text = match x {
+                        Operand::Register(x) => format!("if {} != 0 {{ {} }}", REGISTER_NAMES[x], jump),
+                        Operand::Value(_) => jump,
+                    };
+                    self.emit(Some(i), depth, &text);
+                }
+                Instruction::Tgl(x) => {
+                    let offset = group.value(x);
+                    self.flush(&mut group, depth, i + 1);
+                    let text = match offset {
+                        Expr::Const(offset) => format!("toggle {}", i as i64 + offset),
+                        _ => format!("toggle {} + {}", i, x),
+                    };
+                    self.emit(Some(i), depth, &text);
+                }
+                Instruction::Out(x) => {
+                    self.flush(&mut group, depth, i + 1);
+                    self.emit(Some(i), depth, &format!("out {}", x));
+                }
+            }
+            i += 1;
+        }
+        self.flush(&mut group, depth, end);
+    }
+
+    /// Structures a forward `jnz x` at `i` as an `if`, returning where the code continues.
+    fn branch(&mut self, x: usize, i: usize, target: usize, end: usize, depth: usize) -> usize {
+        // `jnz x 2` over a `jnz 1 n` runs the code up to the second target only if x is not zero.
+        if target == i + 2 {
+            if let Instruction::Jnz(Operand::Value(condition), Operand::Value(offset)) = self.program[i + 1] {
+                let skip = i as i64 + 1 + offset;
+                if condition != 0 && skip > target as i64 && skip <= end as i64 {
+                    self.emit(Some(i), depth, &format!("if {} != 0 {{", REGISTER_NAMES[x]));
+                    self.block(target, skip as usize, depth + 1);
+                    self.emit(None, depth, "}");
+                    return skip as usize;
+                }
+            }
+        }
+        self.emit(Some(i), depth, &format!("if {} == 0 {{", REGISTER_NAMES[x]));
+        self.block(i + 1, target, depth + 1);
+        self.emit(None, depth, "}");
+        target
+    }
+}
+
+/// Turns a program into pseudocode. Loops come from backward `jnz`s, and the
+/// loops the optimizer recognizes are folded into additions and multiplications
+/// together with the straight-line code around them. Every line starts with the
+/// position of its first instruction.
+pub fn decompile(program: &[Instruction]) -> String {
+    let ops = optimizer::optimize(program);
+    // Jumps inside the loops that are folded do not split the code around them.
+    let mut folded = HashSet::new();
+    for (start, op) in ops.iter().enumerate() {
+        if let Op::Loop(found) = op {
+            folded.extend(start..start + found.len);
+        }
+    }
+    let mut targets: HashSet<usize> = program
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !folded.contains(i))
+        .filter_map(|(i, instruction)| match instruction {
+            Instruction::Jnz(Operand::Value(0), _) => None,
+            Instruction::Jnz(_, Operand::Value(offset)) => usize::try_from(i as i64 + offset).ok(),
+            _ => None,
+        })
+        .collect();
+    // The code is split at the targets of computed jumps too, which are only found
+    // while decompiling, so it is decompiled again until no new ones show up.
+    loop {
+        let mut decompiler = Decompiler {
+            program,
+            ops: ops.clone(),
+            targets: targets.clone(),
+            computed: HashSet::new(),
+            loops: vec![],
+            lines: vec![],
+        };
+        if program.iter().any(|instruction| matches!(instruction, Instruction::Tgl(_))) {
+            decompiler.lines.push(String::from("// tgl changes the program while it runs, this is the code as written"));
+        }
+        decompiler.block(0, program.len(), 0);
+        if decompiler.computed.is_subset(&targets) {
+            return decompiler.lines.join("\n") + "\n";
+        }
+        targets.extend(decompiler.computed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decompile;
+    use crate::assembunny::parse_program;
+
+    #[test]
+    fn multiplication() {
+        let program = parse_program("cpy c a\ncpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5\nout a").unwrap();
+        let expected = [
+            "   0 | // assuming d > 0 and b > 0",
+            "     | a = c + b * d",
+            "     | c = 0",
+            "     | d = 0",
+            "   7 | out a",
+        ];
+        assert_eq!(decompile(&program), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn control_flow() {
+        let program = parse_program("cpy 1 a\njnz c 2\njnz 1 3\ncpy 2 a\ninc b\ncpy a c\ninc a\ndec b\njnz b -2\ncpy c b\ndec d\njnz d -6").unwrap();
+        let expected = [
+            "   0 | a = 1",
+            "   1 | if c != 0 {",
+            "   3 |     a = 2",
+            "     |     b = b + 1",
+            "     | }",
+            "   5 | do {",
+            "   5 |     // assuming b > 0",
+            "     |     c = a",
+            "     |     d = d - 1",
+            "     |     (a, b) = (a + b, a)",
+            "     | } while d != 0",
+        ];
+        assert_eq!(decompile(&program), expected.join("\n") + "\n");
+    }
+}
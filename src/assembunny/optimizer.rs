@@ -0,0 +1,175 @@
+use super::{Instruction, Operand, Registers};
+
+/// The optimized form of one program position.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    /// Run the instruction as it is.
+    Exec(Instruction),
+    /// A loop starting at this position that can be applied at once.
+    Loop(Loop),
+}
+
+/// The inner loop of a nested loop, restarted from `source` on every outer iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inner {
+    pub source: Operand,
+    pub counter: usize,
+    /// Change of every register per inner iteration.
+    pub deltas: Registers,
+}
+
+/// A loop that counts a register down to zero, changing the other registers
+/// by the same amount on every iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loop {
+    pub counter: usize,
+    pub inner: Option<Inner>,
+    /// Change of every register per outer iteration, outside of the inner loop.
+    pub deltas: Registers,
+    /// Number of instructions from the start of the loop to its back-edge included.
+    pub len: usize,
+}
+
+impl Loop {
+    /// Applies every iteration of the loop at once. Returns false, leaving the
+    /// registers untouched, if the loop would not terminate by counting down.
+    pub fn apply(&self, registers: &mut Registers) -> bool {
+        let times = registers[self.counter];
+        if times <= 0 {
+            return false;
+        }
+        let mut change = self.deltas;
+        if let Some(inner) = &self.inner {
+            let repeat = inner.source.value(registers);
+            if repeat <= 0 {
+                return false;
+            }
+            for (change, delta) in change.iter_mut().zip(inner.deltas) {
+                *change += delta * repeat;
+            }
+        }
+        for (register, change) in registers.iter_mut().zip(change) {
+            *register += change * times;
+        }
+        registers[self.counter] = 0;
+        if let Some(inner) = &self.inner {
+            registers[inner.counter] = 0;
+        }
+        true
+    }
+}
+
+/// Replaces every loop the optimizer understands with a [`Loop`] at its first
+/// instruction. The instructions of the loop are kept, so jumps into its middle
+/// and loops whose guard fails still run as written.
+pub fn optimize(program: &[Instruction]) -> Vec<Op> {
+    let mut ops = program.iter().map(|instruction| Op::Exec(*instruction)).collect::<Vec<Op>>();
+    for end in 0..program.len() {
+        if let Some((start, found)) = find_loop(program, end) {
+            if let Op::Exec(_) = ops[start] {
+                ops[start] = Op::Loop(found);
+            }
+        }
+    }
+    ops
+}
+
+/// Returns the start of the backward `jnz` at `end`, with the register it tests.
+fn back_edge(program: &[Instruction], end: usize) -> Option<(usize, usize)> {
+    match program[end] {
+        Instruction::Jnz(Operand::Register(counter), Operand::Value(offset)) if offset < 0 => {
+            let start = end.checked_sub(offset.unsigned_abs() as usize)?;
+            Some((start, counter))
+        }
+        _ => None,
+    }
+}
+
+/// Sums the effect of a straight run of `inc` and `dec` instructions.
+fn deltas(body: &[Instruction]) -> Option<Registers> {
+    let mut deltas = [0; 4];
+    for instruction in body {
+        match instruction {
+            Instruction::Inc(Operand::Register(x)) => deltas[*x] += 1,
+            Instruction::Dec(Operand::Register(x)) => deltas[*x] -= 1,
+            _ => return None,
+        }
+    }
+    Some(deltas)
+}
+
+fn find_loop(program: &[Instruction], end: usize) -> Option<(usize, Loop)> {
+    let (start, counter) = back_edge(program, end)?;
+    let body = &program[start..end];
+    let len = end - start + 1;
+    if let Some(deltas) = deltas(body) {
+        return (deltas[counter] == -1).then_some((start, Loop { counter, inner: None, deltas, len }));
+    }
+    let inner = (start..end).find_map(|inner_end| {
+        let (inner_start, inner_counter) = back_edge(program, inner_end)?;
+        match program[inner_start.checked_sub(1)?] {
+            Instruction::Cpy(source, Operand::Register(to)) if to == inner_counter && inner_start > start => {
+                Some((inner_start - 1, inner_end, source, inner_counter))
+            }
+            _ => None,
+        }
+    });
+    let (copy, inner_end, source, inner_counter) = inner?;
+    let inner_deltas = deltas(&program[copy + 1..inner_end])?;
+    let outer_deltas = add(deltas(&program[start..copy])?, deltas(&program[inner_end + 1..end])?);
+    let source_changes = match source {
+        Operand::Register(register) => {
+            register == counter || register == inner_counter || inner_deltas[register] != 0 || outer_deltas[register] != 0
+        }
+        Operand::Value(_) => false,
+    };
+    if inner_counter == counter
+        || source_changes
+        || inner_deltas[inner_counter] != -1
+        || inner_deltas[counter] != 0
+        || outer_deltas[counter] != -1
+        || outer_deltas[inner_counter] != 0
+    {
+        return None;
+    }
+    let inner = Inner {
+        source,
+        counter: inner_counter,
+        deltas: inner_deltas,
+    };
+    Some((start, Loop { counter, inner: Some(inner), deltas: outer_deltas, len }))
+}
+
+fn add(a: Registers, b: Registers) -> Registers {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{optimize, Op};
+    use crate::assembunny::{parse_program, Operand};
+
+    #[test]
+    fn loops() {
+        let program = parse_program("cpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5\ninc b\ndec a\njnz a -2").unwrap();
+        let ops = optimize(&program);
+        let Op::Loop(nested) = &ops[0] else { panic!("nested loop not found") };
+        assert_eq!(nested.counter, 3);
+        assert_eq!(nested.inner.as_ref().unwrap().source, Operand::Register(1));
+        assert_eq!(nested.len, 6);
+        assert!(matches!(ops[1], Op::Loop(_)));
+        let Op::Loop(after) = &ops[6] else { panic!("loop after the nested loop not found") };
+        assert_eq!(after.deltas, [-1, 1, 0, 0]);
+        let mut registers = [1, 3, 0, 4];
+        assert!(nested.apply(&mut registers));
+        assert_eq!(registers, [13, 3, 0, 0]);
+    }
+
+    #[test]
+    fn unsafe_loops_are_kept() {
+        let source_changes = parse_program("cpy b c\ninc a\ndec c\njnz c -2\ninc b\ndec d\njnz d -6").unwrap();
+        assert!(matches!(optimize(&source_changes)[0], Op::Exec(_)));
+        let counts_up = parse_program("inc a\ninc d\njnz d -2").unwrap();
+        assert!(matches!(optimize(&counts_up)[0], Op::Exec(_)));
+    }
+}
@@ -0,0 +1,285 @@
+use std::{
+    fs, panic,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common,
+    json,
+    registry,
+    runner::{self, JobConfig, PARTS},
+    timing,
+};
+
+pub struct BenchConfig {
+    days: Vec<i32>,
+    parts: Vec<i32>,
+    repeat: usize,
+    report: Option<String>,
+}
+
+impl BenchConfig {
+    /// Parses `[<day> [<part>]] [--repeat <n>] [--report <file.json|file.csv>]`.
+    pub fn new(args: &[String]) -> Result<BenchConfig, String> {
+        let mut positional: Vec<i32> = vec![];
+        let mut repeat = 5;
+        let mut report = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--repeat" => {
+                    let value = args.next().ok_or("Missing value for --repeat")?;
+                    repeat = match value.parse::<usize>() {
+                        Ok(repeat) if repeat > 0 => repeat,
+                        _ => return Err(format!("--repeat must be a positive integer, got {}", value)),
+                    };
+                }
+                "--report" => {
+                    let path = args.next().ok_or("Missing value for --report")?;
+                    if !path.ends_with(".json") && !path.ends_with(".csv") {
+                        return Err(String::from("Report file must end with .json or .csv"));
+                    }
+                    report = Some(path.clone());
+                }
+                value => match value.parse::<i32>() {
+                    Ok(number) => positional.push(number),
+                    Err(e) => return Err(format!("Cannot parse {} to i32: {}", value, e)),
+                },
+            }
+        }
+        let days = match positional.first() {
+            Some(day) if registry::find(*day).is_some() => vec![*day],
+            Some(day) => return Err(format!("Day {} is not registered", day)),
+            None => registry::days().collect(),
+        };
+        let parts = match positional.get(1) {
+            Some(part) if PARTS.contains(part) => vec![*part],
+            Some(_) => return Err(String::from("Part parameter must be 1 or 2")),
+            None => PARTS.collect(),
+        };
+        if positional.len() > 2 {
+            return Err(String::from("Too many arguments for bench"));
+        }
+        Ok(BenchConfig {
+            days,
+            parts,
+            repeat,
+            report,
+        })
+    }
+}
+
+pub struct Stats {
+    min: Duration,
+    median: Duration,
+    max: Duration,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct BenchResult {
+    day: i32,
+    part: i32,
+    outcome: Result<(Stats, Stats), String>,
+}
+
+impl BenchResult {
+    pub fn is_failure(&self) -> bool {
+        self.outcome.is_err()
+    }
+}
+
+/// Solves every selected (day, part) `repeat` times on the real data,
+/// splitting each run into the time spent parsing and the time spent solving.
+pub fn run(config: &BenchConfig) -> Vec<BenchResult> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut results = vec![];
+    for day in &config.days {
+        for part in &config.parts {
+            results.push(bench(*day, *part, config.repeat));
+        }
+    }
+    panic::set_hook(default_hook);
+    results
+}
+
+fn bench(day: i32, part: i32, repeat: usize) -> BenchResult {
+    let input = match common::read_input(&JobConfig { day, part }) {
+        Ok(input) => input,
+        Err(err) => {
+            return BenchResult {
+                day,
+                part,
+                outcome: Err(format!("cannot read input file: {}", err)),
+            }
+        }
+    };
+    let mut parse_samples = vec![];
+    let mut solve_samples = vec![];
+    for _ in 0..repeat {
+        timing::take_parse_time();
+        let start = Instant::now();
+        let run = runner::solve(day, part, &input, &[]);
+        let total = start.elapsed();
+        let parse = timing::take_parse_time();
+        if let Err(err) = run {
+            return BenchResult {
+                day,
+                part,
+                outcome: Err(err),
+            };
+        }
+        parse_samples.push(parse);
+        solve_samples.push(total.saturating_sub(parse));
+    }
+    BenchResult {
+        day,
+        part,
+        outcome: Ok((Stats::new(parse_samples), Stats::new(solve_samples))),
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn print_table(results: &[BenchResult]) {
+    println!(
+        "{:>3} | {:>4} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}",
+        "Day", "Part", "parse min", "parse med", "parse max", "solve min", "solve med", "solve max"
+    );
+    println!("----+------+{}", ["------------"; 6].join("+"));
+    for result in results {
+        match &result.outcome {
+            Ok((parse, solve)) => println!(
+                "{:>3} | {:>4} | {:>10.3} | {:>10.3} | {:>10.3} | {:>10.3} | {:>10.3} | {:>10.3}",
+                result.day,
+                result.part,
+                millis(parse.min),
+                millis(parse.median),
+                millis(parse.max),
+                millis(solve.min),
+                millis(solve.median),
+                millis(solve.max)
+            ),
+            Err(err) => println!("{:>3} | {:>4} | FAILED ({})", result.day, result.part, err),
+        }
+    }
+    println!("All times in milliseconds");
+}
+
+fn to_csv(results: &[BenchResult]) -> String {
+    let mut lines = vec![String::from(
+        "day,part,parse_min_ms,parse_median_ms,parse_max_ms,solve_min_ms,solve_median_ms,solve_max_ms,error",
+    )];
+    for result in results {
+        lines.push(match &result.outcome {
+            Ok((parse, solve)) => format!(
+                "{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},",
+                result.day,
+                result.part,
+                millis(parse.min),
+                millis(parse.median),
+                millis(parse.max),
+                millis(solve.min),
+                millis(solve.median),
+                millis(solve.max)
+            ),
+            Err(err) => format!(
+                "{},{},,,,,,,\"{}\"",
+                result.day,
+                result.part,
+                err.replace('"', "\"\"").replace('\n', " ")
+            ),
+        });
+    }
+    lines.join("\n") + "\n"
+}
+
+fn stats_to_json(stats: &Stats) -> String {
+    format!(
+        "{{\"min_ms\": {:.3}, \"median_ms\": {:.3}, \"max_ms\": {:.3}}}",
+        millis(stats.min),
+        millis(stats.median),
+        millis(stats.max)
+    )
+}
+
+fn to_json(results: &[BenchResult], repeat: usize) -> String {
+    let entries = results
+        .iter()
+        .map(|result| match &result.outcome {
+            Ok((parse, solve)) => format!(
+                "  {{\"day\": {}, \"part\": {}, \"runs\": {}, \"parse\": {}, \"solve\": {}}}",
+                result.day,
+                result.part,
+                repeat,
+                stats_to_json(parse),
+                stats_to_json(solve)
+            ),
+            Err(err) => format!(
+                "  {{\"day\": {}, \"part\": {}, \"error\": {}}}",
+                result.day,
+                result.part,
+                json::string(err)
+            ),
+        })
+        .collect::<Vec<String>>();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+pub fn write_report(config: &BenchConfig, results: &[BenchResult]) -> Result<(), String> {
+    let path = match &config.report {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let report = if path.ends_with(".json") {
+        to_json(results, config.repeat)
+    } else {
+        to_csv(results)
+    };
+    fs::write(path, report).map_err(|e| format!("Cannot write report {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BenchConfig, Stats};
+
+    #[test]
+    fn stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis).to_vec();
+        let stats = Stats::new(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.max, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn config() {
+        let args = ["11", "--repeat", "3"].map(String::from);
+        let config = BenchConfig::new(&args).unwrap();
+        assert_eq!(config.days, vec![11]);
+        assert_eq!(config.parts, vec![1, 2]);
+        assert_eq!(config.repeat, 3);
+        assert!(BenchConfig::new(&["--repeat", "0"].map(String::from)).is_err());
+        assert!(BenchConfig::new(&["--report", "out.txt"].map(String::from)).is_err());
+    }
+}
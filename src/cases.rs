@@ -0,0 +1,259 @@
+use std::{fs, thread};
+
+use crate::{common, input::Input, registry, runner};
+
+/// One expected answer from `inputs/dayN/cases.toml`. Slow cases only run with
+/// `cargo test -- --ignored`.
+#[derive(Debug, PartialEq)]
+pub struct Case {
+    pub file: String,
+    pub part: i32,
+    pub answer: String,
+    pub params: Vec<(String, String)>,
+    pub slow: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    Text(String),
+    Int(i64),
+    Bool(bool),
+    Table(Vec<(String, String)>),
+}
+
+/// Parses the subset of TOML the manifests use: `[[case]]` headers followed by
+/// `key = value` lines, where a value is a string, an integer, a boolean or an
+/// inline table of strings and integers.
+///
+/// ```toml
+/// [[case]]
+/// file = "data1.txt"
+/// part = 1
+/// answer = "6"
+/// params = { rows = 3 }
+/// slow = true
+/// ```
+///
+/// `params` and `slow` are optional.
+pub fn parse(text: &str) -> Result<Vec<Case>, String> {
+    let mut tables: Vec<Vec<(String, Value)>> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let at_line = |message: String| format!("line {}: {}", i + 1, message);
+        if line == "[[case]]" {
+            tables.push(vec![]);
+            continue;
+        }
+        let table = tables
+            .last_mut()
+            .ok_or_else(|| at_line(String::from("expected [[case]] first")))?;
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| at_line(format!("expected key = value, got '{}'", line)))?;
+        table.push((key.trim().to_string(), parse_value(value.trim()).map_err(at_line)?));
+    }
+    tables
+        .into_iter()
+        .enumerate()
+        .map(|(i, table)| to_case(table).map_err(|message| format!("case {}: {}", i + 1, message)))
+        .collect()
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    if let Some(inner) = text.strip_prefix('{').and_then(|text| text.strip_suffix('}')) {
+        let mut pairs = vec![];
+        for pair in split_outside_quotes(inner) {
+            let pair = pair.trim();
+            if pair.is_empty() {
+                continue;
+            }
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected key = value in table, got '{}'", pair))?;
+            let value = match parse_value(value.trim())? {
+                Value::Text(text) => text,
+                Value::Int(number) => number.to_string(),
+                _ => return Err(format!("table values must be strings or integers, got '{}'", value.trim())),
+            };
+            pairs.push((key.trim().to_string(), value));
+        }
+        return Ok(Value::Table(pairs));
+    }
+    if let Some(inner) = text.strip_prefix('"').and_then(|text| text.strip_suffix('"')) {
+        return unescape(inner).map(Value::Text);
+    }
+    match text {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => text
+            .parse::<i64>()
+            .map(Value::Int)
+            .map_err(|_| format!("expected a string, integer, boolean or table, got '{}'", text)),
+    }
+}
+
+fn split_outside_quotes(text: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            other => return Err(format!("unknown escape '\\{}'", other.map(String::from).unwrap_or_default())),
+        }
+    }
+    Ok(result)
+}
+
+fn to_case(table: Vec<(String, Value)>) -> Result<Case, String> {
+    let mut file = None;
+    let mut part = None;
+    let mut answer = None;
+    let mut params = vec![];
+    let mut slow = false;
+    for (key, value) in table {
+        match (key.as_str(), value) {
+            ("file", Value::Text(text)) => file = Some(text),
+            ("part", Value::Int(number @ 1..=2)) => part = Some(number as i32),
+            ("answer", Value::Text(text)) => answer = Some(text),
+            ("params", Value::Table(pairs)) => params = pairs,
+            ("slow", Value::Bool(flag)) => slow = flag,
+            (key, value) => return Err(format!("unexpected {} = {:?}", key, value)),
+        }
+    }
+    Ok(Case {
+        file: file.ok_or("missing file")?,
+        part: part.ok_or("missing part, expected 1 or 2")?,
+        answer: answer.ok_or("missing answer")?,
+        params,
+        slow,
+    })
+}
+
+/// Runs the cases of every registered day, one thread per day, and returns a
+/// description of every case that failed.
+fn run_all(slow: bool) -> Vec<String> {
+    thread::scope(|scope| {
+        let handles = registry::days()
+            .map(|day| (day, scope.spawn(move || run_day(day, slow))))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|(day, handle)| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| vec![format!("day {}: a case panicked", day)])
+            })
+            .collect()
+    })
+}
+
+fn run_day(day: i32, slow: bool) -> Vec<String> {
+    let dir = common::inputs_root().join(format!("day{}", day));
+    let path = dir.join("cases.toml");
+    // Every registered day has a manifest, so a missing one is a failure rather than no cases.
+    let manifest = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) => return vec![format!("{}: cannot read the manifest: {}", path.display(), err)],
+    };
+    let cases = match parse(&manifest) {
+        Ok(cases) => cases,
+        Err(err) => return vec![format!("{}, {}", path.display(), err)],
+    };
+    let mut failures = vec![];
+    for case in cases.into_iter().filter(|case| case.slow == slow) {
+        let name = format!("day {} part {} on {}", day, case.part, case.file);
+        let input = match fs::read_to_string(dir.join(&case.file)) {
+            Ok(raw) => Input::new(&raw),
+            Err(err) => {
+                failures.push(format!("{}: cannot read input: {}", name, err));
+                continue;
+            }
+        };
+        match runner::solve(day, case.part, &input, &case.params) {
+            Ok(answer) if answer.to_string() == case.answer => (),
+            Ok(answer) => failures.push(format!("{}: expected {:?}, got {:?}", name, case.answer, answer.to_string())),
+            Err(err) => failures.push(format!("{}: {}", name, err)),
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, run_all, Case};
+
+    #[test]
+    fn manifest() {
+        let text = "# comment\n[[case]]\nfile = \"data1.txt\" # trailing\npart = 2\nanswer = \"a#b\\n\"\nparams = { rows = 3, name = \"x, y\" }\nslow = true\n";
+        let expected = Case {
+            file: String::from("data1.txt"),
+            part: 2,
+            answer: String::from("a#b\n"),
+            params: vec![(String::from("rows"), String::from("3")), (String::from("name"), String::from("x, y"))],
+            slow: true,
+        };
+        assert_eq!(parse(text), Ok(vec![expected]));
+        assert!(parse("file = \"data.txt\"").unwrap_err().starts_with("line 1"));
+        assert!(parse("[[case]]\nfile = \"data.txt\"\npart = 3\nanswer = \"1\"").is_err());
+        assert!(parse("[[case]]\npart = 1").unwrap_err().contains("missing file"));
+    }
+
+    #[test]
+    fn cases() {
+        let failures = run_all(false);
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    #[ignore]
+    fn slow_cases() {
+        let failures = run_all(true);
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -1,6 +1,16 @@
-use std::{env, fmt::Display, fs, io, any::Any};
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
 
-use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25};
+use crate::answer::Answer;
+use crate::error::PuzzleError;
+use crate::input::Input;
+use crate::params::{self, Param, Params};
+use crate::registry;
 
 #[derive(Copy, Clone)]
 pub enum Data {
@@ -17,26 +27,96 @@ impl Display for Data {
     }
 }
 
+impl Data {
+    /// Parses a data set given as `real` or a test number.
+    pub fn parse(arg: &str) -> Result<Data, String> {
+        match arg {
+            "real" => Ok(Data::Real),
+            s => s
+                .parse::<i32>()
+                .map(Data::Test)
+                .map_err(|e| format!("Data parameter must be 'real' or i32: {}", e)),
+        }
+    }
+
+    /// The data set as it is given on the command line: `real` or the test number.
+    pub fn name(&self) -> String {
+        match self {
+            Data::Real => String::from("real"),
+            Data::Test(number) => number.to_string(),
+        }
+    }
+}
+
+/// How results are printed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    /// One JSON object per solved part.
+    Json,
+}
+
+impl Format {
+    pub fn new(name: &str) -> Result<Format, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => Err(format!("Format must be text or json, got {}", other)),
+        }
+    }
+}
+
+/// Environment variable naming the directory that holds the `dayN/` input folders.
+pub const INPUTS_VAR: &str = "AOC_INPUTS";
+
 pub trait Config {
     fn get_day(&self) -> i32;
     fn get_part(&self) -> i32;
     fn get_data_type(&self) -> Data;
+    /// A file to read the input from instead of the data file, `-` for stdin.
+    fn get_input(&self) -> Option<&str> {
+        None
+    }
+    fn get_inputs_root(&self) -> PathBuf {
+        inputs_root()
+    }
+}
+
+/// The inputs directory: `$AOC_INPUTS` if set, else `inputs` in the working
+/// directory, else the one in the source tree the binary was built from.
+pub fn inputs_root() -> PathBuf {
+    if let Some(root) = env::var_os(INPUTS_VAR) {
+        return PathBuf::from(root);
+    }
+    let local = Path::new("inputs");
+    if local.is_dir() {
+        return local.to_path_buf();
+    }
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")
 }
 
 pub struct EnvConfig {
     day: i32,
     part: i32,
     data_type: Data,
-    extra: Option<Box<dyn Any>>
+    params: Vec<(String, String)>,
+    input: Option<String>,
+    inputs_root: Option<PathBuf>,
+    format: Format,
 }
 
 impl EnvConfig {
     pub fn new() -> Result<EnvConfig, String> {
         let args: Vec<String> = env::args().collect();
         if args.len() < 4 {
-            return Err(String::from("Invalid number of arguments, wanted >=4, got ")
+            return Err(String::from("Invalid number of arguments, wanted >=4 (or `all`), got ")
                 + args.len().to_string().as_str());
         }
+        // The data set may be left out when --input says where the input is.
+        let (data_arg, flags) = match args[3].starts_with("--") {
+            true => (None, &args[3..]),
+            false => (Some(args[3].as_str()), &args[4..]),
+        };
         let day = match args[1].parse::<i32>() {
             Ok(day) => day,
             Err(e) => {
@@ -45,8 +125,8 @@ impl EnvConfig {
                 )
             }
         };
-        if day < 1 || day > 25 {
-            return Err(String::from("Day parameter must be between 1 and 25"));
+        if registry::find(day).is_none() {
+            return Err(format!("Day {} is not registered", day));
         }
         let part = match args[2].parse::<i32>() {
             Ok(part) => part,
@@ -56,27 +136,67 @@ impl EnvConfig {
                 )
             }
         };
-        if part < 1 || part > 2 {
+        if !(1..=2).contains(&part) {
             return Err(String::from("Part parameter must be 1 or 2"));
         }
-        let data = match args[3].as_str() {
-            "real" => Data::Real,
-            s => match s.parse::<i32>() {
+        let data = match data_arg {
+            None | Some("real") => Data::Real,
+            Some(s) => match s.parse::<i32>() {
                 Ok(number) => Data::Test(number),
                 Err(e) => return Err(String::from("Fourth parameter must be 'real' or i32: ") + e.to_string().as_str())
             }
         };
-        let mut extra: Option<Box<dyn Any>> = None;
-        if args.len() > 4 {
-            extra = Some(Box::new(args[4..].join(" ")))
+        let mut params = vec![];
+        let mut input = None;
+        let mut inputs_root = None;
+        let mut format = Format::Text;
+        let mut rest = flags.iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--param" => match rest.next() {
+                    Some(assignment) => params.push(params::parse_assignment(assignment)?),
+                    None => return Err(String::from("Missing key=value after --param")),
+                },
+                "--input" => match rest.next() {
+                    Some(path) => input = Some(path.clone()),
+                    None => return Err(String::from("Missing value for --input")),
+                },
+                "--inputs" => match rest.next() {
+                    Some(dir) => inputs_root = Some(PathBuf::from(dir)),
+                    None => return Err(String::from("Missing value for --inputs")),
+                },
+                "--format" => match rest.next() {
+                    Some(name) => format = Format::new(name)?,
+                    None => return Err(String::from("Missing value for --format")),
+                },
+                other => return Err(String::from("Unexpected argument: ") + other),
+            }
+        }
+        if data_arg.is_none() && input.is_none() {
+            return Err(String::from("Fourth parameter must be 'real' or i32 unless --input is given"));
         }
         Ok(EnvConfig {
             day,
             part,
             data_type: data,
-            extra
+            params,
+            input,
+            inputs_root,
+            format,
         })
     }
+
+    pub fn get_format(&self) -> Format {
+        self.format
+    }
+
+    /// The data set for reports: the `--input` path if there is one, else the data set name.
+    pub fn get_data_name(&self) -> String {
+        match &self.input {
+            Some(path) => path.clone(),
+            None => self.data_type.name(),
+        }
+    }
 }
 
 impl Config for EnvConfig {
@@ -91,85 +211,100 @@ impl Config for EnvConfig {
     fn get_data_type(&self) -> Data {
         self.data_type
     }
+
+    fn get_input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+
+    fn get_inputs_root(&self) -> PathBuf {
+        self.inputs_root.clone().unwrap_or_else(inputs_root)
+    }
 }
 
-pub fn read_input(config: &impl Config) -> Result<String, io::Error> {
-    let suffix = match config.get_data_type() {
+/// The data file of `day` under `root`: `dayN/data.txt` for the real data,
+/// `dayN/dataM.txt` for test data `M`.
+pub fn data_path(root: &Path, day: i32, data_type: Data) -> PathBuf {
+    let suffix = match data_type {
         Data::Real => String::from(""),
         Data::Test(number) => number.to_string(),
     };
-    let filepath = format!("inputs/day{}/data{}.txt", config.get_day(), suffix);
-    fs::read_to_string(filepath)
-}
-
-pub trait Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String;
-    fn part_2(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String;
-}
-
-pub fn solve(input: String, config: EnvConfig) -> String {
-    let solver: Box<dyn Puzzle> = match config.day {
-        1 => Box::new(day1::Puzzle{}),
-        2 => Box::new(day2::Puzzle{}),
-        3 => Box::new(day3::Puzzle{}),
-        4 => Box::new(day4::Puzzle{}),
-        5 => Box::new(day5::Puzzle{}),
-        6 => Box::new(day6::Puzzle{}),
-        7 => Box::new(day7::Puzzle{}),
-        8 => Box::new(day8::Puzzle{}),
-        9 => Box::new(day9::Puzzle{}),
-        10 => Box::new(day10::Puzzle{}),
-        11 => Box::new(day11::Puzzle{}),
-        12 => Box::new(day12::Puzzle{}),
-        13 => Box::new(day13::Puzzle{}),
-        14 => Box::new(day14::Puzzle{}),
-        15 => Box::new(day15::Puzzle{}),
-        16 => Box::new(day16::Puzzle{}),
-        17 => Box::new(day17::Puzzle{}),
-        18 => Box::new(day18::Puzzle{}),
-        19 => Box::new(day19::Puzzle{}),
-        20 => Box::new(day20::Puzzle{}),
-        21 => Box::new(day21::Puzzle{}),
-        22 => Box::new(day22::Puzzle{}),
-        23 => Box::new(day23::Puzzle{}),
-        24 => Box::new(day24::Puzzle{}),
-        25 => Box::new(day25::Puzzle{}),
-        n => panic!("Day {} not implemented yet", n),
+    root.join(format!("day{}", day)).join(format!("data{}.txt", suffix))
+}
+
+pub fn read_input(config: &impl Config) -> Result<Input, io::Error> {
+    let raw = match config.get_input() {
+        Some("-") => {
+            let mut raw = String::new();
+            io::stdin().read_to_string(&mut raw)?;
+            raw
+        }
+        Some(path) => read_file(Path::new(path))?,
+        None => read_file(&data_path(&config.get_inputs_root(), config.get_day(), config.get_data_type()))?,
+    };
+    Ok(Input::new(&raw))
+}
+
+fn read_file(path: &Path) -> Result<String, io::Error> {
+    fs::read_to_string(path).map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))
+}
+
+/// Puzzles are shared between the worker threads of run-all, hence `Send + Sync`.
+pub trait Puzzle: Send + Sync {
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError>;
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError>;
+    /// The parameters both parts accept through `--param key=value`.
+    fn params(&self) -> Vec<Param> {
+        vec![]
+    }
+}
+
+pub fn solve(input: &Input, config: EnvConfig) -> Result<Answer, PuzzleError> {
+    solve_part(config.day, config.part, input, &config.params)
+}
+
+/// Validates `overrides` against the puzzle's parameters and solves one part.
+/// Errors are tagged with the day they come from.
+pub fn solve_part(
+    day: i32,
+    part: i32,
+    input: &Input,
+    overrides: &[(String, String)],
+) -> Result<Answer, PuzzleError> {
+    let solver = match registry::find(day) {
+        Some(entry) => entry.puzzle,
+        None => return Err(PuzzleError::new(format!("Day {} is not registered", day))),
     };
-    match config.get_part() {
-        1 => solver.part_1(input, config.extra),
-        2 => solver.part_2(input, config.extra),
-        p => panic!("Invalid part {}", p)
+    if !(1..=2).contains(&part) {
+        return Err(PuzzleError::new(format!("Invalid part {}", part)).in_day(day));
     }
+    let solution = Params::resolve(&solver.params(), part, overrides)
+        .map_err(PuzzleError::new)
+        .and_then(|params| match part {
+            1 => solver.part_1(input, &params),
+            _ => solver.part_2(input, &params),
+        });
+    solution.map_err(|err| err.in_day(day))
 }
 
 #[cfg(test)]
-pub mod common_test {
-    use super::{Config, Data};
+mod tests {
+    use std::path::Path;
 
-    pub struct FakeConfig {
-        day: i32,
-        part: i32, 
-        data_type: Data,
-    }
+    use super::{data_path, solve_part, Data};
+    use crate::input::Input;
 
-    impl FakeConfig {
-        pub fn new(day: i32, part: i32, data_type: Data) -> FakeConfig {
-            FakeConfig{day, part, data_type}
-        }
+    #[test]
+    fn data_paths() {
+        let root = Path::new("mine");
+        assert_eq!(data_path(root, 3, Data::Real), Path::new("mine/day3/data.txt"));
+        assert_eq!(data_path(root, 12, Data::Test(2)), Path::new("mine/day12/data2.txt"));
     }
 
-    impl Config for FakeConfig {
-        fn get_day(&self) -> i32 {
-            self.day
-        }
-    
-        fn get_part(&self) -> i32 {
-            self.part
-        }
-    
-        fn get_data_type(&self) -> Data {
-            self.data_type
+    #[test]
+    fn invalid_part() {
+        for part in [0, 3] {
+            let error = solve_part(18, part, &Input::new(".^^."), &[]).unwrap_err();
+            assert_eq!(error.to_string(), format!("day 18: Invalid part {}", part));
         }
     }
-}
\ No newline at end of file
+}
@@ -1,195 +1,126 @@
-use std::{any::Any, collections::HashMap, fmt::Display};
-
-use crate::common;
+use std::collections::HashMap;
+
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    geometry::{Direction, Point, Turn},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 1,
+    name: "No Time for a Taxicab",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let instructions = create_instructions(input);
-        let mut me = Me::new();
-        for instruction in instructions {
-            me.step(instruction);
-        }
-        me.pos.distance().to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let instructions = timing::parse(|| parse(input))?;
+        Ok(part_1(&instructions).into())
     }
 
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let instructions = create_instructions(input);
-        let mut me = Me::new();
-        for instruction in instructions {
-            if let Some(_) = me.step_2(instruction) {
-                break;
-            }
-        }
-        me.pos.distance().to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let instructions = timing::parse(|| parse(input))?;
+        Ok(part_2(&instructions).into())
     }
 }
 
-fn create_instructions(data: String) -> Vec<Instruction> {
-    let mut instructions: Vec<Instruction> = vec![];
-    let parts = data.split(", ");
-    for part in parts {
-        instructions.push(Instruction::new(part))
+/// Blocks between the start and the end of the instructions.
+pub fn part_1(instructions: &[Instruction]) -> u32 {
+    let mut me = Me::new();
+    for instruction in instructions {
+        me.step(instruction);
     }
-    instructions
+    me.distance()
 }
 
-#[derive(Debug)]
-enum Turn {
-    Left,
-    Right,
-}
-
-impl Turn {
-    fn new(data: Option<char>) -> Turn {
-        match data {
-            Some('L') => Turn::Left,
-            Some('R') => Turn::Right,
-            _ => panic!("Inalvid input"),
+/// Blocks between the start and the first location visited twice, or the end if there is none.
+pub fn part_2(instructions: &[Instruction]) -> u32 {
+    let mut me = Me::new();
+    for instruction in instructions {
+        if me.step_2(instruction).is_some() {
+            break;
         }
     }
+    me.distance()
 }
 
-#[derive(Debug)]
-struct Instruction {
-    turn: Turn,
-    amount: i32,
-}
-
-impl Instruction {
-    fn new(data: &str) -> Instruction {
-        let mut chars = data.chars();
-        let turn = Turn::new(chars.next());
-        let amount = chars.collect::<String>().parse().unwrap();
-        Instruction {
-            turn: turn,
-            amount: amount,
-        }
+/// Parses the comma separated instructions of the document.
+pub fn parse(input: &Input) -> Result<Vec<Instruction>, PuzzleError> {
+    let mut instructions: Vec<Instruction> = vec![];
+    let parts = input.trimmed().split(", ");
+    for part in parts {
+        let instruction = Instruction::new(part).map_err(|err| PuzzleError::new(err).with_text(part))?;
+        instructions.push(instruction)
     }
+    Ok(instructions)
 }
 
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    fn new() -> Direction {
-        Direction::North
+fn parse_turn(data: Option<char>) -> Result<Turn, String> {
+    match data {
+        Some('L') => Ok(Turn::Left),
+        Some('R') => Ok(Turn::Right),
+        Some(c) => Err(format!("turn must be L or R, got '{}'", c)),
+        None => Err(String::from("empty instruction")),
     }
-    fn turn(&self, t: Turn) -> Direction {
-        match *self {
-            Direction::North => match t {
-                Turn::Left => Direction::West,
-                Turn::Right => Direction::East,
-            },
-            Direction::East => match t {
-                Turn::Left => Direction::North,
-                Turn::Right => Direction::South,
-            },
-            Direction::South => match t {
-                Turn::Left => Direction::East,
-                Turn::Right => Direction::West,
-            },
-            Direction::West => match t {
-                Turn::Left => Direction::South,
-                Turn::Right => Direction::North,
-            },
-        }
-    }
-}
-
-#[derive(Eq, PartialEq, Hash, Clone, Copy)]
-struct Coord {
-    x: i32,
-    y: i32,
 }
 
-impl Display for Coord {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
+#[derive(Debug)]
+pub struct Instruction {
+    pub turn: Turn,
+    pub amount: i32,
 }
 
-impl Coord {
-    fn new() -> Coord {
-        Coord { x: 0, y: 0 }
-    }
-    fn step(&mut self, amount: i32, dir: &Direction) {
-        match dir {
-            Direction::North => self.y += amount,
-            Direction::East => self.x += amount,
-            Direction::South => self.y -= amount,
-            Direction::West => self.x -= amount,
-        }
-    }
-
-    fn distance(&self) -> i32 {
-        self.x.abs() + self.y.abs()
+impl Instruction {
+    fn new(data: &str) -> Result<Instruction, String> {
+        let mut chars = data.chars();
+        let turn = parse_turn(chars.next())?;
+        let amount = error::number(Some(chars.as_str()), "a number of blocks")?;
+        Ok(Instruction {
+            turn,
+            amount,
+        })
     }
 }
 
 struct Me {
     dir: Direction,
-    pos: Coord,
-    visited: HashMap<Coord, i32>,
+    pos: Point<i32>,
+    visited: HashMap<Point<i32>, i32>,
 }
 
 impl Me {
     fn new() -> Me {
         Me {
-            dir: Direction::new(),
-            pos: Coord::new(),
+            dir: Direction::North,
+            pos: Point::origin(),
             visited: HashMap::new(),
         }
     }
 
-    fn step(&mut self, ins: Instruction) {
+    fn distance(&self) -> u32 {
+        self.pos.manhattan(Point::origin()) as u32
+    }
+
+    fn step(&mut self, ins: &Instruction) {
         self.dir = self.dir.turn(ins.turn);
-        self.pos.step(ins.amount, &self.dir);
+        self.pos = self.pos.step_by(self.dir, ins.amount);
     }
 
-    fn step_2(&mut self, ins: Instruction) -> Option<()> {
+    fn step_2(&mut self, ins: &Instruction) -> Option<()> {
         self.dir = self.dir.turn(ins.turn);
         for _ in 0..ins.amount {
-            if let Some(_) = self.visited.insert(self.pos, 1) {
+            if self.visited.insert(self.pos, 1).is_some() {
                 return Some(());
             }
-            self.pos.step(1, &self.dir);
+            self.pos = self.pos.step(self.dir);
         }
         None
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases = vec![
-            (Data::Test(1), "5"),
-            (Data::Test(2), "2"),
-            (Data::Real, "209"),
-        ];
-        for case in cases {
-            let solution = crate::day1::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(1, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases = vec![(Data::Test(3), "4"), (Data::Real, "136")];
-        for case in cases {
-            let solution = crate::day1::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(1, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
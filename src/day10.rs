@@ -1,29 +1,69 @@
-use std::{collections::HashMap, fmt::Debug, any::Any};
-
-use crate::common;
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
-const DEFAULT_SEARCH: Search = Search(61, 17);
+pub const ENTRY: Entry = Entry {
+    day: 10,
+    name: "Balance Bots",
+    puzzle: &Puzzle {},
+};
 
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let search: Search = match extra_param {
-            Some(b) => (*b.downcast_ref::<Search>().unwrap()).clone(),
-            None => DEFAULT_SEARCH,
-        };
-        let mut factory = Factory::new(input);
-        factory.work(search).unwrap().to_string()
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let (chip_a, chip_b) = (params.int("chip_a") as i32, params.int("chip_b") as i32);
+        let factory = timing::parse(|| parse(input))?;
+        match part_1(factory, chip_a, chip_b) {
+            Some(bot) => Ok(bot.into()),
+            None => Err(PuzzleError::new(format!(
+                "no bot compares chips {} and {}",
+                chip_a, chip_b
+            ))),
+        }
+    }
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let factory = timing::parse(|| parse(input))?;
+        Ok(part_2(factory)?.into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let mut factory = Factory::new(input);
-        factory.work(Search(-1, -1));
-        let mut product = 1;
-        for i in 0..3 {
-            product *= factory.get_output(&i).chips[0];
+    fn params(&self) -> Vec<Param> {
+        let kind = Kind::Int { min: 0, max: i32::MAX as i64 };
+        vec![
+            Param::new("chip_a", kind, ["61", "61"], "one of the chips the wanted bot compares"),
+            Param::new("chip_b", kind, ["17", "17"], "the other chip the wanted bot compares"),
+        ]
+    }
+}
+
+/// The number of the bot that compares `chip_a` with `chip_b`.
+pub fn part_1(mut factory: Factory, chip_a: i32, chip_b: i32) -> Option<i32> {
+    factory.work(Search(chip_a, chip_b))
+}
+
+/// The product of the chips that end up in outputs 0, 1 and 2.
+pub fn part_2(mut factory: Factory) -> Result<i32, PuzzleError> {
+    factory.work(Search(-1, -1));
+    let mut product = 1;
+    for i in 0..3 {
+        match factory.get_output(&i).chips.first() {
+            Some(chip) => product *= chip,
+            None => return Err(PuzzleError::new(format!("output {} receives no chip", i))),
         }
-        product.to_string()
     }
+    Ok(product)
+}
+
+/// Reads the starting chips and the instructions of every bot.
+pub fn parse(input: &Input) -> Result<Factory, PuzzleError> {
+    Factory::new(input)
 }
 
 #[derive(Clone, Copy)]
@@ -36,33 +76,31 @@ impl Search {
 }
 
 #[derive(Debug)]
-struct Factory {
+pub struct Factory {
     robots: HashMap<i32, Bot>,
     outputs: HashMap<i32, Output>,
 }
 
 impl Factory {
-    fn new(input: String) -> Factory {
-        let lines = input.split("\n");
+    fn new(input: &Input) -> Result<Factory, PuzzleError> {
         let mut f = Factory {
             outputs: HashMap::new(),
             robots: HashMap::new(),
         };
-        for line in lines {
+        error::parse_lines(input.lines(), |line| {
             if line.starts_with("value") {
                 let mut numbers = line
                     .split(" ")
-                    .map(|word| word.parse::<i32>())
-                    .filter(|result| result.is_ok())
-                    .map(|result| result.unwrap());
-                let chip = numbers.next().unwrap();
-                let bot_number = numbers.next().unwrap();
+                    .flat_map(|word| word.parse::<i32>());
+                let chip = numbers.next().ok_or("missing chip value")?;
+                let bot_number = numbers.next().ok_or("missing bot number")?;
                 f.add_chip_to_bot(chip, bot_number);
+                Ok(())
             } else {
-                f.parse_command(line);
-            };
-        }
-        f
+                f.parse_command(line)
+            }
+        })?;
+        Ok(f)
     }
 
     fn work(&mut self, search: Search) -> Option<i32> {
@@ -71,8 +109,7 @@ impl Factory {
             if search.contains(&result[0].chip) && search.contains(&result[1].chip) {
                 return Some(bot_number);
             }
-            for i in 0..2 {
-                let giving = &result[i];
+            for giving in &result {
                 if giving.is_bot {
                     self.get_bot(&giving.to).add_chip(giving.chip);
                 } else {
@@ -100,7 +137,7 @@ impl Factory {
         bot.add_chip(chip);
     }
 
-    fn parse_command(&mut self, line: &str) {
+    fn parse_command(&mut self, line: &str) -> Result<(), String> {
         let words = line.split(" ");
         let mut numbers: Vec<i32> = vec![];
         let mut keywords: Vec<&str> = vec![];
@@ -112,6 +149,11 @@ impl Factory {
                 keywords.push(word);
             }
         }
+        if numbers.len() != 3 || keywords.len() != 3 || keywords[0] != "bot" {
+            return Err(String::from(
+                "expected 'bot N gives low to bot|output N and high to bot|output N'",
+            ));
+        }
         let bot_number = numbers[0];
         let low_number = numbers[1];
         let high_number = numbers[2];
@@ -124,6 +166,7 @@ impl Factory {
         if keywords[2] == "output" {
             self.get_output(&high_number);
         }
+        Ok(())
     }
 
     fn get_bot(&mut self, number: &i32) -> &mut Bot {
@@ -131,33 +174,22 @@ impl Factory {
     }
 
     fn get_output(&mut self, number: &i32) -> &mut Output {
-        self.outputs.entry(*number).or_insert(Output::new(number))
+        self.outputs.entry(*number).or_insert(Output::new())
     }
 }
 
 trait Bin {
     fn add_chip(&mut self, chip: i32);
-    fn get_number(&self) -> i32;
-}
-
-impl Debug for dyn Bin {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Bin{{{:?}}}", self)
-    }
 }
 
 #[derive(Debug)]
 struct Output {
-    number: i32,
     chips: Vec<i32>,
 }
 
 impl Output {
-    fn new(number: &i32) -> Output {
-        Output {
-            chips: vec![],
-            number: *number,
-        }
+    fn new() -> Output {
+        Output { chips: vec![] }
     }
 }
 
@@ -165,9 +197,6 @@ impl Bin for Output {
     fn add_chip(&mut self, chip: i32) {
         self.chips.push(chip);
     }
-    fn get_number(&self) -> i32 {
-        self.number
-    }
 }
 
 struct Bot {
@@ -269,42 +298,17 @@ impl Bin for Bot {
     fn add_chip(&mut self, chip: i32) {
         self.chips.push(chip);
     }
-    fn get_number(&self) -> i32 {
-        self.number
-    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::any::Any;
-
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    use super::Search;
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "2", Some(Box::new(Search(2, 5)))),
-            (Data::Real, "86", None)
-        ];
-        for case in cases {
-            let solution = crate::day10::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(10, 1, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
-    }
+    use crate::{common, input::Input};
 
     #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Real, "22847")
-        ];
-        for case in cases {
-            let solution = crate::day10::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(10, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    fn malformed_input() {
+        let input = Input::new("value 5 goes to bot 2\nbot 2 gives low to bot");
+        let err = common::solve_part(10, 2, &input, &[]).unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.text.as_deref(), Some("bot 2 gives low to bot"));
     }
 }
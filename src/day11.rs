@@ -1,42 +1,72 @@
 use std::{
-    any::Any,
     collections::HashMap,
     fmt::Debug,
     hash::{Hash, Hasher},
 };
 
-use crate::common;
-use pathfinding;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+    search::{self, Path},
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 11,
+    name: "Radioisotope Thermoelectric Generators",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let start = Area::new(input);
-        let result = pathfinding::astar(
-            &start,
-            Area::neighbours,
-            Area::heuristic_cost,
-            Area::finished,
-        );
-        result.unwrap().1.to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let start = timing::parse(|| parse(input))?;
+        Ok(part_1(&start)?.into())
+    }
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let start = timing::parse(|| parse(input))?;
+        Ok(part_2(&start)?.into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let mut start = Area::new(input);
-        start.pairs.push(CGPair{chip: 0, generator: 0, elem: String::from("elerium")});
-        start.pairs.push(CGPair{chip: 0, generator: 0, elem: String::from("dilithium")});
-        let result = pathfinding::astar(
-            &start,
-            Area::neighbours,
-            Area::heuristic_cost,
-            Area::finished,
-        );
-        result.unwrap().1.to_string()
+}
+
+/// The fewest elevator trips that bring every item to the top floor.
+pub fn part_1(start: &Area) -> Result<usize, PuzzleError> {
+    let search = search::astar(
+        start.clone(),
+        Area::neighbours,
+        Area::heuristic_cost,
+        Area::finished,
+    );
+    solution(search.path)
+}
+
+/// The fewest trips with an elerium and a dilithium pair added to the first floor.
+pub fn part_2(start: &Area) -> Result<usize, PuzzleError> {
+    let mut start = start.clone();
+    start.pairs.push(CGPair{chip: 0, generator: 0, elem: String::from("elerium")});
+    start.pairs.push(CGPair{chip: 0, generator: 0, elem: String::from("dilithium")});
+    part_1(&start)
+}
+
+/// Reads which floor every generator and microchip starts on.
+pub fn parse(input: &Input) -> Result<Area, PuzzleError> {
+    Area::new(input)
+}
+
+fn solution(path: Option<Path<Area, usize>>) -> Result<usize, PuzzleError> {
+    match path {
+        Some(path) => Ok(path.cost),
+        None => Err(PuzzleError::new("the items cannot be brought to the top floor")),
     }
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
-struct Area {
+pub struct Area {
     pairs: Vec<CGPair>,
     current: usize,
 }
@@ -49,21 +79,27 @@ impl Hash for Area {
 }
 
 impl Area {
-    fn new(input: String) -> Area {
+    fn new(input: &Input) -> Result<Area, PuzzleError> {
         let mut pairs: HashMap<String, CGPair> = HashMap::new();
-        for (i, line) in input.split("\n").enumerate() {
+        for (i, line) in input.lines().enumerate() {
+            if i > 3 {
+                return Err(PuzzleError::new("the building has only 4 floors").at_line(i + 1, line));
+            }
             let mut words = line.split(' ').rev();
             while let Some(word) = words.next() {
                 match word {
                     "generator" | "generator," | "generator." => {
-                        let elem = words.next().unwrap().to_string();
+                        let elem = words.next().ok_or_else(|| missing_element(i, line))?.to_string();
                         pairs
                             .entry(elem.to_string())
                             .or_insert(CGPair::new(elem))
                             .generator = i;
                     }
                     "microchip" | "microchip," | "microchip." => {
-                        let elem = words.next().unwrap().replace("-compatible", "").to_string();
+                        let elem = words
+                            .next()
+                            .ok_or_else(|| missing_element(i, line))?
+                            .replace("-compatible", "");
                         pairs
                             .entry(elem.to_string())
                             .or_insert(CGPair::new(elem))
@@ -73,16 +109,15 @@ impl Area {
                 }
             }
         }
-        let mut pairs = pairs.iter().map(|(_, p)| p.clone()).collect::<Vec<CGPair>>();
+        let mut pairs = pairs.values().cloned().collect::<Vec<CGPair>>();
         pairs.sort();
-        Area { pairs, current: 0 }
+        Ok(Area { pairs, current: 0 })
     }
 
     fn get_items_as_vec(&self, floor: usize) -> Vec<String> {
         self.pairs
             .iter()
-            .map(|pair| pair.on_floor(floor))
-            .flatten()
+            .flat_map(|pair| pair.on_floor(floor))
             .collect::<Vec<String>>()
     }
 
@@ -123,7 +158,7 @@ impl Area {
         neighbors
             .iter()
             .filter(|(neighbour, _)| neighbour.is_valid())
-            .map(|neighbour| neighbour.clone())
+            .cloned()
             .collect()
     }
 
@@ -136,7 +171,7 @@ impl Area {
             if !items
                 .iter()
                 .filter(|item| is_chip(item))
-                .all(|chip| has_generator(&items, &chip))
+                .all(|chip| has_generator(&items, chip))
             {
                 return false;
             }
@@ -144,7 +179,7 @@ impl Area {
         true
     }
 
-    fn move_item(&mut self, to: usize, item: &String) {
+    fn move_item(&mut self, to: usize, item: &str) {
         let elem = &item[..item.len() - 2];
         let generator = is_generator(item);
         if generator {
@@ -165,7 +200,7 @@ impl Area {
 
     fn get_valid_items(items: Vec<String>) -> Vec<Vec<String>> {
         let mut result: Vec<Vec<String>> = vec![];
-        if items.len() == 0 {
+        if items.is_empty() {
             return result;
         }
         let generators = items.iter().filter(|item| is_generator(item)).count();
@@ -189,18 +224,18 @@ impl Area {
                 if is_chip(item_1) && is_chip(item_2) {
                     result.push(Vec::from([item_1.to_string(), item_2.to_string()]));
                 }
-                if (is_chip(item_1) && is_generator(item_2))
-                    || (is_generator(item_1) && is_chip(item_2))
+                if ((is_chip(item_1) && is_generator(item_2))
+                    || (is_generator(item_1) && is_chip(item_2)))
+                    && item_1[..item_1.len() - 1] == item_2[..item_2.len() - 1]
                 {
-                    if item_1[..item_1.len() - 1] == item_2[..item_2.len() - 1] {
-                        result.push(Vec::from([item_1.to_string(), item_2.to_string()]));
-                        continue;
-                    }
+                    result.push(Vec::from([item_1.to_string(), item_2.to_string()]));
+                    continue;
                 }
-                if is_generator(item_1) && is_generator(item_2) {
-                    if generators == 2 || (!has_chip(&items, item_1) && !has_chip(&items, item_2)) {
-                        result.push(Vec::from([item_1.to_string(), item_2.to_string()]));
-                    }
+                if is_generator(item_1)
+                    && is_generator(item_2)
+                    && (generators == 2 || (!has_chip(&items, item_1) && !has_chip(&items, item_2)))
+                {
+                    result.push(Vec::from([item_1.to_string(), item_2.to_string()]));
                 }
             }
         }
@@ -218,19 +253,23 @@ impl Area {
     }
 }
 
-fn is_chip(item: &String) -> bool {
-    item.chars().last().unwrap() == 'M'
+fn missing_element(i: usize, line: &str) -> PuzzleError {
+    PuzzleError::new("item without an element").at_line(i + 1, line)
 }
 
-fn is_generator(item: &String) -> bool {
-    item.chars().last().unwrap() == 'G'
+fn is_chip(item: &str) -> bool {
+    item.ends_with('M')
 }
 
-fn has_chip(items: &Vec<String>, elem: &String) -> bool {
+fn is_generator(item: &str) -> bool {
+    item.ends_with('G')
+}
+
+fn has_chip(items: &[String], elem: &str) -> bool {
     items.contains(&(elem[..elem.len() - 1].to_string() + "M"))
 }
 
-fn has_generator(items: &Vec<String>, elem: &String) -> bool {
+fn has_generator(items: &[String], elem: &str) -> bool {
     items.contains(&(elem[..elem.len() - 1].to_string() + "G"))
 }
 
@@ -274,32 +313,3 @@ impl CGPair {
         items
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "11"), (Data::Real, "37")];
-        for case in cases {
-            let solution = crate::day11::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(11, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    #[ignore]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Real, "61")
-        ];
-        for case in cases {
-            let solution = crate::day11::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(11, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
@@ -1,106 +1,53 @@
-use std::{any::Any, collections::HashMap};
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    assembunny::{self, Engine, Instruction, Registers},
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 12,
+    name: "Leonardo's Monorail",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let commands = create_commands(input);
-        let mut computer = Computer::new();
-        let mut i = 0;
-        while i < commands.len() {
-            i = (i as i32 + computer.execute(&commands[i])) as usize
-        }
-        computer.registers["a"].to_string()
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        run(input, params)
+    }
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        run(input, params)
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let commands = create_commands(input);
-        let mut computer = Computer::new();
-        computer.registers.entry("c".to_owned()).and_modify(|v| *v = 1);
-        let mut i = 0;
-        while i < commands.len() {
-            i = (i as i32 + computer.execute(&commands[i])) as usize
-        }
-        computer.registers["a"].to_string()
+    fn params(&self) -> Vec<Param> {
+        let kind = Kind::Int { min: i32::MIN as i64, max: i32::MAX as i64 };
+        vec![
+            Param::new("c", kind, ["0", "1"], "initial value of register c"),
+            assembunny::engine_param(),
+        ]
     }
 }
 
-fn create_commands(input: String) -> Vec<String> {
-    input.split("\n").map(|command| command.to_owned()).collect::<Vec<String>>()
+fn run(input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+    let program = timing::parse(|| parse(input))?;
+    Ok(solve(program, registers(params), Engine::from_params(params))?.into())
 }
 
-struct Computer {
-    registers: HashMap<String, i32>
+pub fn parse(input: &Input) -> Result<Vec<Instruction>, PuzzleError> {
+    assembunny::parse_program(input.text())
 }
 
-impl Computer {
-    fn new() -> Computer {
-        let mut registers = HashMap::new();
-        for i in 'a'..='d' {
-            registers.insert(i.to_string(), 0);
-        }
-        Computer { registers }
-    }
-
-    fn execute(&mut self, command: &String) -> i32{
-        let mut words = command.split(" ");
-        match words.next().unwrap() {
-            "cpy" => {
-                let from = words.next().unwrap();
-                let value = self.get_value(from);
-                let to = words.next().unwrap();
-                self.registers.entry(to.to_owned()).and_modify(|v| *v = value);
-            },
-            "inc" => {self.registers.entry(words.next().unwrap().to_owned()).and_modify(|v| *v += 1);},
-            "dec"  => {self.registers.entry(words.next().unwrap().to_owned()).and_modify(|v| *v -= 1);},
-            "jnz" => {
-                if self.get_value(words.next().unwrap()) != 0 {
-                    return words.next().unwrap().parse::<i32>().unwrap();
-                }
-            }
-            _ => panic!("Invalid command")
-        };
-        1
-    }
-
-    fn get_value(&self, data: &str) -> i32 {
-        match data.parse::<i32>() {
-            Ok(i) => i,
-            Err(_) => self.registers[data],
-        }
-    }
+/// Register a after running the program from `registers`.
+pub fn solve(program: Vec<Instruction>, registers: Registers, engine: Engine) -> Result<i64, PuzzleError> {
+    Ok(assembunny::run_program(program, registers, engine)?[0])
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "42"),
-            (Data::Real, "318077")
-        ];
-        for case in cases {
-            let solution = crate::day12::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(12, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    #[ignore]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "42"),
-            (Data::Real, "9227731")
-        ];
-        for case in cases {
-            let solution = crate::day12::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(12, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+/// The registers the program starts with: all zero except c, which comes from the parameters.
+pub fn registers(params: &Params) -> Registers {
+    [0, 0, params.int("c"), 0]
 }
@@ -1,178 +1,102 @@
-use std::{any::Any, collections::HashSet, hash::Hash};
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    geometry::{Direction, Point},
+    grid::{Grid, Pos},
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    search,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 13,
+    name: "A Maze of Twisty Little Cubicles",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let number = input.parse::<i32>().unwrap();
-        let goal = parse_extra(extra_param, number);
-        let start = Coordinate::new(1, 1, number, 0);
-        let result = pathfinding::astar(
-            &start,
-            Coordinate::neighbours,
-            |node| node.manhattan_dist(&goal),
-            |node| *node == goal,
-        );
-        result.unwrap().1.to_string()
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let number = timing::parse(|| parse(input))?;
+        Ok(part_1(number, params.int("x") as i32, params.int("y") as i32)?.into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let number = input.parse::<i32>().unwrap();
-        let start = Coordinate::new(1, 1, number, 0);
-        let mut visited: HashSet<Coordinate> = HashSet::new();
-        let mut found: HashSet<Coordinate> = HashSet::new();
-        found.insert(start);
-        while found.len() != 0 {
-            let mut plus: HashSet<Coordinate> = HashSet::new();
-            for node in &found {
-                for n in node.neighbours() {
-                    if n.0.dist <= 50 {
-                        plus.insert(n.0);
-                    }
-                }
-            }
-            let plus = plus
-                .iter()
-                .filter(|n| !found.contains(&n) && !visited.contains(&n))
-                .map(Coordinate::clone)
-                .collect::<Vec<Coordinate>>();
-            for node in found {
-                visited.insert(node);
-            }
-            found = plus
-                .iter()
-                .map(Coordinate::clone)
-                .collect::<HashSet<Coordinate>>();
-        }
-        visited.iter().len().to_string()
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let number = timing::parse(|| parse(input))?;
+        Ok(part_2(number, params.int("steps") as i32).into())
     }
-}
-
-fn parse_extra(param: Option<Box<dyn Any>>, number: i32) -> Coordinate {
-    match param {
-        None => Coordinate::new(31, 39, number, 0),
-        Some(b) => {
-            let data = *b.downcast::<String>().unwrap();
-            let mut coords = data.split(" ");
-            let x = coords.next().unwrap().parse::<i32>().unwrap();
-            let y = coords.next().unwrap().parse::<i32>().unwrap();
-            Coordinate::new(x, y, number, 0)
-        }
+    fn params(&self) -> Vec<Param> {
+        let coordinate = Kind::Int { min: 0, max: 1000 };
+        let steps = Kind::Int { min: 0, max: 500 };
+        vec![
+            Param::new("x", coordinate, ["31", "31"], "x coordinate of the target location"),
+            Param::new("y", coordinate, ["39", "39"], "y coordinate of the target location"),
+            Param::new("steps", steps, ["50", "50"], "most steps taken when counting reachable locations"),
+        ]
     }
 }
 
-#[derive(Clone, Eq)]
-struct Coordinate {
-    x: i32,
-    y: i32,
-    is_wall: bool,
-    number: i32,
-    dist: i32,
-}
-
-impl Hash for Coordinate {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.x.hash(state);
-        self.y.hash(state);
+/// The fewest steps from 1,1 to `x`,`y` in the office of the designer's favorite `number`.
+pub fn part_1(number: i32, x: i32, y: i32) -> Result<usize, PuzzleError> {
+    let goal = Point::new(x as i64, y as i64);
+    if is_wall(number, goal.x, goal.y) {
+        return Err(PuzzleError::new(format!("{} is a wall", goal)));
     }
-}
-
-impl PartialEq for Coordinate {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
+    let search = search::astar(
+        Point::new(1, 1),
+        |point| open_around(number, *point).map(|point| (point, 1)),
+        |point| point.manhattan(goal) as usize,
+        |point| *point == goal,
+    );
+    match search.path {
+        Some(path) => Ok(path.cost),
+        None => Err(PuzzleError::new(format!("{} cannot be reached", goal))),
     }
 }
 
-impl Coordinate {
-    fn new(x: i32, y: i32, number: i32, dist: i32) -> Coordinate {
-        let mut c = Coordinate {
-            x,
-            y,
-            is_wall: false,
-            number,
-            dist,
-        };
-        c.is_wall = c.compute_is_wall();
-        c
-    }
-
-    fn compute_is_wall(&self) -> bool {
-        let x = self.x;
-        let y = self.y;
-        let num = x * x + 3 * x + 2 * x * y + y + y * y + self.number;
-        num.count_ones() % 2 == 1
-    }
+/// How many locations can be reached from 1,1 in at most `steps` steps.
+pub fn part_2(number: i32, steps: i32) -> usize {
+    let steps = steps as usize;
+    // Nothing further than `steps` right of or below 1,1 can be reached.
+    let office = office(number, steps + 2, steps + 2);
+    search::reachable(Pos::new(1, 1), |pos| open_neighbours(&office, *pos), Some(steps))
+        .distances
+        .len()
+}
 
-    fn neighbours(&self) -> Vec<(Coordinate, i32)> {
-        let mut neighbours: Vec<Coordinate> = vec![];
-        neighbours.push(Coordinate::new(
-            self.x,
-            self.y + 1,
-            self.number,
-            self.dist + 1,
-        ));
-        neighbours.push(Coordinate::new(
-            self.x + 1,
-            self.y,
-            self.number,
-            self.dist + 1,
-        ));
-        if self.x != 0 {
-            neighbours.push(Coordinate::new(
-                self.x - 1,
-                self.y,
-                self.number,
-                self.dist + 1,
-            ));
-        }
-        if self.y != 0 {
-            neighbours.push(Coordinate::new(
-                self.x,
-                self.y - 1,
-                self.number,
-                self.dist + 1,
-            ));
+/// The part of the office from 0,0 to `width`-1,`height`-1, with `true` for walls.
+pub fn office(number: i32, width: usize, height: usize) -> Grid<bool> {
+    let mut office = Grid::new(width, height, false);
+    for y in 0..height {
+        for x in 0..width {
+            office[Pos::new(x, y)] = is_wall(number, x as i64, y as i64);
         }
-        neighbours
-            .iter()
-            .filter(|neighbour| !neighbour.is_wall)
-            .map(|neighbour| (neighbour.clone(), 1 as i32))
-            .collect()
-    }
-
-    fn manhattan_dist(&self, other: &Coordinate) -> i32 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
     }
+    office
 }
 
-#[cfg(test)]
-mod tests {
-    use std::any::Any;
+fn is_wall(number: i32, x: i64, y: i64) -> bool {
+    let num = x * x + 3 * x + 2 * x * y + y + y * y + number as i64;
+    num.count_ones() % 2 == 1
+}
 
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
+/// The open locations next to `point` anywhere in the office, which has no negative coordinates.
+fn open_around(number: i32, point: Point<i64>) -> impl Iterator<Item = Point<i64>> {
+    Direction::CARDINAL
+        .into_iter()
+        .map(move |direction| point.step(direction))
+        .filter(move |next| next.x >= 0 && next.y >= 0 && !is_wall(number, next.x, next.y))
+}
 
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "11", Some(Box::new(String::from("7 4")))),
-            (Data::Real, "96", None),
-        ];
-        for case in cases {
-            let solution = crate::day13::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(13, 1, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
-    }
+fn open_neighbours(office: &Grid<bool>, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    office.neighbours4(pos).filter(|neighbour| !office[*neighbour])
+}
 
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Real, "141")];
-        for case in cases {
-            let solution = crate::day13::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(13, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+/// Reads the office designer's favorite number.
+pub fn parse(input: &Input) -> Result<i32, PuzzleError> {
+    error::number(Some(input.trimmed()), "the office designer's favorite number")
+        .map_err(|err| PuzzleError::new(err).at_line(1, input.text()))
 }
@@ -1,27 +1,50 @@
-use std::{any::Any, char};
+use std::char;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 14,
+    name: "One-Time Pad",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        find_nth_key_index(64, input, 1).to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(input.trimmed()).into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        find_nth_key_index(64, input, 2017).to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(input.trimmed()).into())
     }
 }
 
+/// The index that produces the 64th key from `salt`.
+pub fn part_1(salt: &str) -> i32 {
+    find_nth_key_index(64, salt, 1)
+}
+
+/// The index of the 64th key when every hash is stretched 2016 extra times.
+pub fn part_2(salt: &str) -> i32 {
+    find_nth_key_index(64, salt, 2017)
+}
+
 fn get_hash(s: String) -> String {
     format!("{:x}", md5::compute(s))
 }
 
-fn find_nth_key_index(n: i32, base: String, hashings: i32) -> i32 {
+fn find_nth_key_index(n: i32, base: &str, hashings: i32) -> i32 {
     let mut index = 0;
     let mut keys: Vec<Key> = vec![];
     let mut possible_keys: Vec<Key> = vec![];
-    while keys.len() < n as usize || possible_keys.len() != 0 {
+    while keys.len() < n as usize || !possible_keys.is_empty() {
         let mut hash = get_hash(format!("{}{}", base, index));
         for _ in 0..hashings - 1 {
             hash = get_hash(hash);
@@ -91,41 +114,8 @@ impl Key {
         Key { index, c }
     }
 
-    fn contains_five(&self, hash: &String) -> bool {
+    fn contains_five(&self, hash: &str) -> bool {
         let fives = self.c.to_string().repeat(5);
         hash.contains(&fives)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "22728"),
-            (Data::Real, "15168")
-        ];
-        for case in cases {
-            let solution = crate::day14::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(14, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    #[ignore]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "22551"),
-            (Data::Real, "20864")
-        ];
-        for case in cases {
-            let solution = crate::day14::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(14, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
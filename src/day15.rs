@@ -1,29 +1,52 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 15,
+    name: "Timing is Everything",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let discs = parse_input(input);
-        find_time(discs).to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let discs = timing::parse(|| parse(input))?;
+        Ok(part_1(&discs).into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let mut discs = parse_input(input);
-        discs.push(Disc::new(11, 0));
-        find_time(discs).to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let discs = timing::parse(|| parse(input))?;
+        Ok(part_2(&discs).into())
     }
 }
 
-#[derive(Debug)]
-struct Disc {
-    positions: i32,
-    start: i32,
+/// The first time the button can be pressed to get a capsule through every disc.
+pub fn part_1(discs: &[Disc]) -> i32 {
+    find_time(discs)
+}
+
+/// The first time with a disc of 11 positions added below the others.
+pub fn part_2(discs: &[Disc]) -> i32 {
+    let mut discs = discs.to_vec();
+    discs.push(Disc::new(11, 0));
+    find_time(&discs)
+}
+
+#[derive(Clone, Debug)]
+pub struct Disc {
+    pub positions: i32,
+    pub start: i32,
 }
 
 impl Disc {
-    fn new(positions: i32, start: i32) -> Disc {
+    pub fn new(positions: i32, start: i32) -> Disc {
         Disc { positions, start }
     }
     fn is_at_zero(&self, time: i32) -> bool {
@@ -31,23 +54,22 @@ impl Disc {
     }
 }
 
-fn parse_input(input: String) -> Vec<Disc> {
-    let mut discs: Vec<Disc> = vec![];
-    for line in input.lines() {
+pub fn parse(input: &Input) -> Result<Vec<Disc>, PuzzleError> {
+    error::parse_lines(input.lines(), |line| {
         let mut parts = line.split_whitespace();
-        let positions = parts.nth(3).unwrap().parse::<i32>().unwrap();
-        let start = parts
-            .last()
-            .unwrap()
-            .trim_end_matches('.')
-            .parse::<i32>()
-            .unwrap();
-        discs.push(Disc::new(positions, start));
-    }
-    discs
+        let positions = error::number(parts.nth(3), "the number of positions")?;
+        if positions <= 0 {
+            return Err(String::from("a disc needs at least one position"));
+        }
+        let start = error::number(
+            parts.last().map(|part| part.trim_end_matches('.')),
+            "the starting position",
+        )?;
+        Ok(Disc::new(positions, start))
+    })
 }
 
-fn find_time(discs: Vec<Disc>) -> i32 {
+fn find_time(discs: &[Disc]) -> i32 {
     let mut time = 0;
     loop {
         let mut fallen = true;
@@ -63,31 +85,3 @@ fn find_time(discs: Vec<Disc>) -> i32 {
         time += 1;
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "5"), (Data::Real, "122318")];
-        for case in cases {
-            let solution = crate::day15::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(15, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Real, "3208583"),
-        ];
-        for case in cases {
-            let solution = crate::day15::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(15, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
@@ -1,32 +1,49 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 16,
+    name: "Dragon Checksum",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let length = parse_extra(extra_param, 1);
-        let mut data = grow(input, length);
-        data = data[0..length].to_string();
-        checksum(data)
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(solve(&timing::parse(|| parse(input))?, params.int("length") as usize).into())
+    }
+
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(solve(&timing::parse(|| parse(input))?, params.int("length") as usize).into())
     }
 
-    fn part_2(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let length = parse_extra(extra_param, 2);
-        let mut data = grow(input, length);
-        data = data[0..length].to_string();
-        checksum(data)
+    fn params(&self) -> Vec<Param> {
+        let kind = Kind::Int { min: 1, max: 35651584 };
+        vec![Param::new("length", kind, ["272", "35651584"], "length of the disk to fill")]
     }
 }
 
-fn parse_extra(param: Option<Box<dyn Any>>, part: i32) -> usize {
-    match param {
-        None => if part == 1 { 272 } else { 35651584 },
-        Some(b) => {
-            let data = *b.downcast::<String>().unwrap();
-            data.parse::<usize>().unwrap()
-        }
+/// The checksum of a disk of `length` filled with the dragon curve grown from `state`.
+pub fn solve(state: &str, length: usize) -> String {
+    let data = grow(state.to_string(), length);
+    checksum(data[0..length].to_string())
+}
+
+/// Reads the initial state, which may only contain 0 and 1.
+pub fn parse(input: &Input) -> Result<String, PuzzleError> {
+    let state = input.trimmed();
+    match state.chars().find(|c| *c != '0' && *c != '1') {
+        Some(c) => Err(PuzzleError::new(format!("the initial state may only contain 0 and 1, got '{}'", c))
+            .at_line(1, state)),
+        None => Ok(state.to_string()),
     }
 }
 
@@ -44,7 +61,7 @@ fn grow(mut input: String, length: usize) -> String {
 }
 
 fn checksum(mut data: String) -> String {
-    while data.len() % 2 == 0 {
+    while data.len().is_multiple_of(2) {
         let odds = data.chars().step_by(2);
         let evens = data.chars().skip(1).step_by(2);
         let pairs = odds.zip(evens);
@@ -54,36 +71,3 @@ fn checksum(mut data: String) -> String {
     }
     data
 }
-
-#[cfg(test)]
-mod tests {
-    use std::any::Any;
-
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "01100", Some(Box::new(String::from("20")))),
-            (Data::Real, "11111000111110000", Some(Box::new(String::from("272")))),
-        ];
-        for case in cases {
-            let solution = crate::day16::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(16, 1, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Real, "10111100110110100", Some(Box::new(String::from("35651584")))),
-        ];
-        for case in cases {
-            let solution = crate::day16::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(16, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
@@ -1,170 +1,107 @@
-use std::{any::Any, collections::HashSet};
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    geometry::{Direction, Point},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    search,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 17,
+    name: "Two Steps Forward",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let start = State::new(0, 0, &input);
-        let full_path = find_best_path(start);
-        full_path.replace(&input, "")
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(input.trimmed())?.into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let start = State::new(0, 0, &input);
-        find_longest_path(start).to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(input.trimmed())?.into())
     }
 }
 
-fn find_best_path(start: State) -> String {
-    let result = pathfinding::astar(
-        &start,
-        |state| state.neighbors(),
-        |state| state.manhatten_distance(),
-        is_vault,
-    )
-    .unwrap();
-    let last = result.0.last().unwrap();
-    last.path.clone()
+/// The moves of the shortest path to the vault.
+pub fn part_1(passcode: &str) -> Result<String, PuzzleError> {
+    let start = State::new(passcode);
+    let full_path = find_best_path(start).ok_or_else(no_path)?;
+    Ok(full_path[passcode.len()..].to_string())
 }
 
-fn find_longest_path(start: State) -> i32 {
-    let base_length = start.path.len() as i32;
-    let mut states = HashSet::new();
-    states.insert(start);
-    let mut longest = 0;
-    let mut new_states = HashSet::new();
-    while !states.is_empty() {
-        for state in states {
-            let neighbors = state.neighbors();
-            for neighbor in neighbors {
-                if is_vault(&neighbor.0) {
-                    longest = longest.max(neighbor.0.path.len() as i32);
-                } else {
-                    new_states.insert(neighbor.0);
-                }
-            }
-        }
-        states = new_states;
-        new_states = HashSet::new();
-    }
-    longest - base_length
+/// The length of the longest path to the vault.
+pub fn part_2(passcode: &str) -> Result<u32, PuzzleError> {
+    let start = State::new(passcode);
+    find_longest_path(start).ok_or_else(no_path)
 }
 
-fn is_vault(state: &State) -> bool {
-    state.x == 3 && state.y == 3
+fn no_path() -> PuzzleError {
+    PuzzleError::new("there is no path to the vault")
 }
 
-#[derive(Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+fn find_best_path(start: State) -> Option<String> {
+    let search = search::astar(start, State::neighbors, |state| state.pos.manhattan(VAULT), is_vault);
+    search.path?.nodes.pop().map(|last| last.path)
 }
 
-impl Direction {
-    fn new(x: usize) -> Direction {
-        match x {
-            0 => Direction::Up,
-            1 => Direction::Down,
-            2 => Direction::Left,
-            3 => Direction::Right,
-            _ => panic!("Invalid direction"),
-        }
-    }
+fn find_longest_path(start: State) -> Option<u32> {
+    let neighbors = |state: &State| state.neighbors().into_iter().map(|(state, _)| state);
+    let (longest, _) = search::longest(start, neighbors, is_vault);
+    longest.map(|longest| longest as u32)
+}
+
+const VAULT: Point<i32> = Point { x: 3, y: 3 };
+
+/// The doors in the order of the hash characters that open them, with the letter of the move.
+const DOORS: [(Direction, char); 4] = [
+    (Direction::North, 'U'),
+    (Direction::South, 'D'),
+    (Direction::West, 'L'),
+    (Direction::East, 'R'),
+];
+
+fn is_vault(state: &State) -> bool {
+    state.pos == VAULT
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 struct State {
-    x: i32,
-    y: i32,
+    pos: Point<i32>,
     path: String,
 }
 
 impl State {
-    fn new(x: i32, y: i32, path: &String) -> State {
+    fn new(path: &str) -> State {
         State {
-            x,
-            y,
-            path: path.clone(),
+            pos: Point::origin(),
+            path: path.to_string(),
         }
     }
 
-    fn open_doors(&self) -> Vec<Direction> {
-        let mut doors = Vec::new();
-        let hash = md5::compute(self.path.as_str());
-        let hash = format!("{:x}", hash);
-        for i in 0..4 {
-            let c = hash.chars().nth(i).unwrap();
-            if c >= 'b' && c <= 'f' {
-                doors.push(Direction::new(i));
-            }
-        }
-        doors
+    fn open_doors(&self) -> Vec<(Direction, char)> {
+        let hash = format!("{:x}", md5::compute(self.path.as_str()));
+        hash.chars()
+            .zip(DOORS)
+            .filter(|(c, _)| ('b'..='f').contains(c))
+            .map(|(_, door)| door)
+            .collect()
     }
 
     fn neighbors(&self) -> Vec<(State, i32)> {
         let mut neighbors = Vec::new();
-        for direction in self.open_doors() {
-            let mut new_state = self.clone();
-            match direction {
-                Direction::Up => new_state.y -= 1,
-                Direction::Down => new_state.y += 1,
-                Direction::Left => new_state.x -= 1,
-                Direction::Right => new_state.x += 1,
-            }
-            if new_state.x < 0 || new_state.x > 3 || new_state.y < 0 || new_state.y > 3 {
+        for (direction, letter) in self.open_doors() {
+            let pos = self.pos.step(direction);
+            if !(0..=3).contains(&pos.x) || !(0..=3).contains(&pos.y) {
                 continue;
             }
-            new_state.path.push(match direction {
-                Direction::Up => 'U',
-                Direction::Down => 'D',
-                Direction::Left => 'L',
-                Direction::Right => 'R',
-            });
-            neighbors.push((new_state, 1));
+            let mut path = self.path.clone();
+            path.push(letter);
+            neighbors.push((State { pos, path }, 1));
         }
         neighbors
     }
-
-    fn manhatten_distance(&self) -> i32 {
-        self.x.abs() + self.y.abs()
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "DDRRRD"),
-            (Data::Test(2), "DDUDRLRRUDRD"),
-            (Data::Test(3), "DRURDRUDDLLDLUURRDULRLDUUDDDRR"),
-            (Data::Real, "RDURRDDLRD"),
-        ];
-        for case in cases {
-            let solution = crate::day17::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(17, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "370"),
-            (Data::Test(2), "492"),
-            (Data::Test(3), "830"),
-            (Data::Real, "526"),
-        ];
-        for case in cases {
-            let solution = crate::day17::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(17, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
 }
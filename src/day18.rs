@@ -1,93 +1,75 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 18,
+    name: "Like a Rogue",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let rows = parse_extra_param(extra_param, 1);
-        let safe_tiles = count_safe_tiles(input, rows);
-        safe_tiles.to_string()
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(count_safe_tiles(timing::parse(|| parse(input))?, params.int("rows") as usize).into())
+    }
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(count_safe_tiles(timing::parse(|| parse(input))?, params.int("rows") as usize).into())
     }
-    fn part_2(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let rows = parse_extra_param(extra_param, 2);
-        let safe_tiles = count_safe_tiles(input, rows);
-        safe_tiles.to_string()
+    fn params(&self) -> Vec<Param> {
+        let kind = Kind::Int { min: 1, max: i32::MAX as i64 };
+        vec![Param::new("rows", kind, ["40", "400000"], "number of rows of tiles to count")]
     }
 }
 
-fn parse_extra_param(extra_param: Option<Box<dyn Any>>, part: i32) -> i32 {
-    match extra_param {
-        Some(param) => match str::parse::<i32>(&param.downcast::<String>().unwrap()) {
-            Ok(param) => param,
-            Err(_) => panic!("Invalid extra param"),
-        },
-        None => if part == 1 { 40 } else { 400000 },
+/// Reads the first row of tiles, which may only contain '^' and '.'. Traps are `true`.
+pub fn parse(input: &Input) -> Result<Vec<bool>, PuzzleError> {
+    let row = input.trimmed();
+    match row.chars().find(|c| *c != '^' && *c != '.') {
+        Some(c) => Err(PuzzleError::new(format!("tiles must be '^' or '.', got '{}'", c)).at_line(1, row)),
+        None => Ok(row.chars().map(|c| c == '^').collect()),
     }
 }
 
-fn next_line(line: String) -> String {
-    let mut traps: Vec<bool> = line.chars().map(|c| c == '^').collect();
-    traps.insert(0, false);
-    traps.push(false);
-    traps.windows(3)
-        .map(|w| prev_traps(w[0], w[1], w[2]))
-        .map(|trap| if trap { '^' } else { '.' })
+/// A tile is a trap when exactly one of the tiles left and right of it in the previous row is.
+fn next_row(previous: &[bool]) -> Vec<bool> {
+    (0..previous.len())
+        .map(|col| {
+            let left = col > 0 && previous[col - 1];
+            let right = previous.get(col + 1).copied().unwrap_or(false);
+            left != right
+        })
         .collect()
 }
 
-fn prev_traps(left: bool, center: bool, right: bool) -> bool {
-    (left && center && !right)
-        || (!left && center && right)
-        || (left && !center && !right)
-        || (!left && !center && right)
-}
-
-fn count_safe_tiles(first_line: String, rows: i32) -> i32 {
-    let mut safe_tiles = count_safe_tiles_in_line(&first_line);
-    let mut line = next_line(first_line);
-    for _i in 1..rows {
-        safe_tiles += count_safe_tiles_in_line(&line);
-        line = next_line(line);
+/// How many safe tiles there are in the first `rows` rows, starting from `first_row`.
+pub fn count_safe_tiles(first_row: Vec<bool>, rows: usize) -> usize {
+    let mut row = first_row;
+    let mut safe = 0;
+    for _ in 0..rows {
+        safe += row.iter().filter(|trap| !**trap).count();
+        row = next_row(&row);
     }
-    safe_tiles
-}
-
-fn count_safe_tiles_in_line(line: &String) -> i32 {
-    line.chars().filter(|c| *c == '.').count() as i32
+    safe
 }
 
 #[cfg(test)]
 mod tests {
-    use std::any::Any;
-
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "6", Some(Box::new(String::from("3")))),
-            (Data::Test(2), "38", Some(Box::new(String::from("10")))),
-            (Data::Real, "1978", Some(Box::new(String::from("40"))))
-        ];
-        for case in cases {
-            let solution = crate::day18::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(18, 1, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
-    }
+    use super::{count_safe_tiles, next_row};
 
     #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Real, "20003246", Some(Box::new(String::from("400000"))))
-        ];
-        for case in cases {
-            let solution = crate::day18::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(18, 2, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
+    fn rows_and_count() {
+        let first_row: Vec<bool> = ".^^.^.^^^^".chars().map(|c| c == '^').collect();
+        let last_row = (1..10).fold(first_row.clone(), |row, _| next_row(&row));
+        let last_row: String = last_row.iter().map(|trap| if *trap { '^' } else { '.' }).collect();
+        assert_eq!(last_row, "^^.^^^..^^");
+        assert_eq!(count_safe_tiles(first_row, 10), 38);
     }
 }
@@ -1,26 +1,49 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 19,
+    name: "An Elephant Named Joseph",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let number_of_elves = get_number_of_elves(&_input);
-        let mut elves = create_elves(number_of_elves);
-        let winner = find_winner(&mut elves);
-        winner.number.to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(timing::parse(|| parse(input))?).into())
     }
-    fn part_2(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let number_of_elves = get_number_of_elves(&_input);
-        let mut elves = create_elves(number_of_elves);
-        let winner = find_winner_2(&mut elves);
-        winner.number.to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(timing::parse(|| parse(input))?).into())
     }
 }
 
-fn get_number_of_elves(input: &str) -> usize {
-    input.parse().unwrap()
+/// The elf that gets all the presents when every elf steals from the next one.
+pub fn part_1(number_of_elves: usize) -> usize {
+    let mut elves = create_elves(number_of_elves);
+    find_winner(&mut elves).number
+}
+
+/// The elf that gets all the presents when every elf steals from the one across the circle.
+pub fn part_2(number_of_elves: usize) -> usize {
+    let mut elves = create_elves(number_of_elves);
+    find_winner_2(&mut elves).number
+}
+
+/// Reads the number of elves, which must be at least one.
+pub fn parse(input: &Input) -> Result<usize, PuzzleError> {
+    match error::number(Some(input.trimmed()), "the number of elves") {
+        Ok(0) => Err(PuzzleError::new("there must be at least one elf").at_line(1, input.text())),
+        Ok(number) => Ok(number),
+        Err(err) => Err(PuzzleError::new(err).at_line(1, input.text())),
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -43,7 +66,7 @@ fn create_elves(number_of_elves: usize) -> Vec<Elf> {
     elves
 }
 
-fn find_winner(elves: &mut Vec<Elf>) -> Elf {
+fn find_winner(elves: &mut [Elf]) -> Elf {
     let mut i = 0;
     loop {
         if elves[i].out {
@@ -72,10 +95,10 @@ fn find_winner(elves: &mut Vec<Elf>) -> Elf {
     panic!("No winner found");
 }
 
-fn find_winner_2(elves: &mut Vec<Elf>) -> Elf {
+fn find_winner_2(elves: &mut [Elf]) -> Elf {
     let mut remaining = elves.len();
     let mut idx = remaining / 2;
-    if remaining % 2 == 0 {
+    if remaining.is_multiple_of(2) {
         while remaining > 1 {
             elves[idx].out = true;
             remaining -= 1;
@@ -112,44 +135,10 @@ fn find_winner_2(elves: &mut Vec<Elf>) -> Elf {
     elves[idx] 
 }
 
-fn get_next(elves: &Vec<Elf>, current: usize) -> usize {
+fn get_next(elves: &[Elf], current: usize) -> usize {
     let mut idx = (current + 1) % elves.len();
     while elves[idx].out {
         idx = (idx + 1) % elves.len();
     }
     idx
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "3"),
-            (Data::Test(2), "5"),
-            (Data::Real, "1830117")
-        ];
-        for case in cases {
-            let solution = crate::day19::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(19, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "2"),
-            (Data::Test(2), "3"),
-            (Data::Real, "1417887")
-        ];
-        for case in cases {
-            let solution = crate::day19::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(19, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
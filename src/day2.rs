@@ -1,39 +1,67 @@
-use std::{any::Any, collections::HashMap};
+use std::collections::HashMap;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 2,
+    name: "Bathroom Security",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let lines = create_lines(input);
-        let mut code: Vec<i32> = vec![];
-        let mut current = 5;
-        for line in lines {
-            current = next_simple(current, line);
-            code.push(current);
-        }
-        code.iter()
-            .map(|number| number.to_string())
-            .collect::<String>()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let lines = timing::parse(|| parse(input))?;
+        Ok(part_1(&lines).into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let lines = create_lines(input);
-        let mut code: Vec<char> = vec![];
-        let mut current = '5';
-        for line in lines {
-            current = next_complex(current, line);
-            code.push(current);
-        }
-        code.iter().collect::<String>()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let lines = timing::parse(|| parse(input))?;
+        Ok(part_2(&lines).into())
+    }
+}
+
+/// The code on the 3x3 keypad, one button per line of moves.
+pub fn part_1(lines: &[String]) -> String {
+    let mut code: Vec<i32> = vec![];
+    let mut current = 5;
+    for line in lines {
+        current = next_simple(current, line);
+        code.push(current);
+    }
+    code.iter()
+        .map(|number| number.to_string())
+        .collect::<String>()
+}
+
+/// The code on the diamond shaped keypad.
+pub fn part_2(lines: &[String]) -> String {
+    let mut code: Vec<char> = vec![];
+    let mut current = '5';
+    for line in lines {
+        current = next_complex(current, line);
+        code.push(current);
     }
+    code.iter().collect::<String>()
 }
 
-fn create_lines(input: String) -> Vec<String> {
-    input.split("\n").map(|line| line.to_string()).collect()
+/// Parses the lines of moves, each made of U, D, L and R.
+pub fn parse(input: &Input) -> Result<Vec<String>, PuzzleError> {
+    error::parse_lines(input.lines(), |line| match line.chars().find(|c| !"UDLR".contains(*c)) {
+        Some(c) => Err(format!("direction must be one of U, D, L or R, got '{}'", c)),
+        None => Ok(line.to_string()),
+    })
 }
 
-fn next_simple(start: i32, line: String) -> i32 {
+fn next_simple(start: i32, line: &str) -> i32 {
     let mut current = start;
     for char in line.chars() {
         match char {
@@ -57,13 +85,13 @@ fn next_simple(start: i32, line: String) -> i32 {
                     current -= 1
                 }
             }
-            _ => panic!("Invalid direction"),
+            _ => unreachable!("directions are checked while parsing"),
         }
     }
     current
 }
 
-fn next_complex(start: char, line: String) -> char {
+fn next_complex(start: char, line: &str) -> char {
     let mut current = start;
     let neighbors: HashMap<char, HashMap<char, char>> = HashMap::from([
         ('1', HashMap::from([('D', '3')])),
@@ -103,29 +131,3 @@ fn next_complex(start: char, line: String) -> char {
     }
     current
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases = vec![(Data::Test(1), "1985"), (Data::Real, "84452")];
-        for case in cases {
-            let solution = crate::day2::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(2, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases = vec![(Data::Test(1), "5DB3"), (Data::Real, "D65C3")];
-        for case in cases {
-            let solution = crate::day2::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(2, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
@@ -1,59 +1,66 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 20,
+    name: "Firewall Rules",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let mut ranges = parse_input(input);
-        let full_range = Range {
-            from: 0,
-            to: parse_extra(extra_param),
-        };
-        ranges = merge_ranges(ranges);
-        println!("Merged ranges: {:?}", ranges.len());
-        match first_non_blocked(&full_range, &ranges) {
-            Ok(value) => return value.to_string(),
-            Err(err) => err,
-        }
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let ranges = timing::parse(|| parse(input))?;
+        Ok(part_1(ranges, params.int("max") as u32)?.into())
+    }
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let ranges = timing::parse(|| parse(input))?;
+        Ok(part_2(ranges, params.int("max") as u32).into())
     }
-    fn part_2(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let mut ranges = parse_input(input);
-        ranges = merge_ranges(ranges);
-        let all = parse_extra(extra_param);
-        let blocked = ranges.iter().map(|r| r.len()).sum::<u32>();
-        (all - blocked + 1).to_string()
+    fn params(&self) -> Vec<Param> {
+        let kind = Kind::Int { min: 0, max: u32::MAX as i64 };
+        vec![Param::new("max", kind, ["4294967295", "4294967295"], "highest allowed IP address")]
     }
 }
 
-fn parse_extra(extra_param: Option<Box<dyn Any>>) -> u32 {
-    match extra_param {
-        Some(param) => str::parse::<u32>(&param.downcast::<String>().unwrap()).unwrap() as u32,
-        None => 4294967295,
-    }
+/// The lowest address up to `max` that no range blocks.
+pub fn part_1(ranges: Vec<Range>, max: u32) -> Result<u32, PuzzleError> {
+    let full_range = Range { from: 0, to: max };
+    first_non_blocked(&full_range, &merge_ranges(ranges))
+}
+
+/// How many addresses up to `max` no range blocks.
+pub fn part_2(ranges: Vec<Range>, max: u32) -> u64 {
+    let blocked = merge_ranges(ranges).iter().map(|r| r.len()).sum::<u64>();
+    (max as u64 + 1).saturating_sub(blocked)
 }
 
+/// An inclusive range of blocked addresses.
 #[derive(Debug, Clone, Copy)]
 pub struct Range {
-    from: u32,
-    to: u32,
+    pub from: u32,
+    pub to: u32,
 }
 
-impl<T> From<T> for Range
-where
-    T: Into<String>,
-{
-    fn from(s: T) -> Self {
-        let binding = s.into();
-        let mut parts = binding.split('-');
-        let from = parts.next().unwrap().parse::<u32>().unwrap();
-        let to = parts.next().unwrap().parse::<u32>().unwrap();
-        Range { from, to }
+impl Range {
+    fn parse(line: &str) -> Result<Range, String> {
+        let mut parts = line.split('-');
+        let from = error::number(parts.next(), "the first blocked address")?;
+        let to = error::number(parts.next(), "the last blocked address")?;
+        if from > to {
+            return Err(format!("range starts after it ends: {} > {}", from, to));
+        }
+        Ok(Range { from, to })
     }
-}
 
-impl Range {
     fn contains(&self, value: u32) -> bool {
         self.from <= value && self.to >= value
     }
@@ -62,8 +69,8 @@ impl Range {
         self.from <= other.to && self.to >= other.from
     }
 
-    fn len(&self) -> u32 {
-        self.to - self.from + 1
+    fn len(&self) -> u64 {
+        (self.to - self.from) as u64 + 1
     }
 
     fn merge(&self, other: &Range) -> Result<Range, String> {
@@ -104,18 +111,18 @@ fn merge_ranges(ranges: Vec<Range>) -> Vec<Range> {
             }
         }
         if !intersected {
-            merged.push(ranges.get(i).unwrap().clone());
+            merged.push(*ranges.get(i).unwrap());
         }
     }
     if success {
         return merge_ranges(merged);
     }
-    return merged;
+    merged
 }   
 
-fn first_non_blocked(range: &Range, ranges: &Vec<Range>) -> Result<u32, String> {
+fn first_non_blocked(range: &Range, ranges: &[Range]) -> Result<u32, PuzzleError> {
     let mut i = 0;
-    while i < range.to {
+    while i <= range.to {
         let mut contained = false;
         for r in ranges {
             if r.contains(i) {
@@ -127,47 +134,26 @@ fn first_non_blocked(range: &Range, ranges: &Vec<Range>) -> Result<u32, String>
         if !contained {
             return Ok(i);
         }
-        i += 1;
+        i = match i.checked_add(1) {
+            Some(next) => next,
+            None => break,
+        };
     }
-    Err("No non-blocked".to_owned())
+    Err(PuzzleError::new(format!("every address up to {} is blocked", range.to)))
 }
 
-fn parse_input(input: String) -> Vec<Range> {
-    input
-        .lines()
-        .map(|line| Range::from(line.to_string()))
-        .collect()
+pub fn parse(input: &Input) -> Result<Vec<Range>, PuzzleError> {
+    error::parse_lines(input.lines(), Range::parse)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::any::Any;
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
+    use super::{part_1, Range};
 
     #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "3"),
-            (Data::Real, "14975795"),
-            ];
-        for case in cases {
-            let solution = crate::day20::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(20, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "2", Some(Box::new(String::from("9")))),
-            (Data::Real, "101", None),
-        ];
-        for case in cases {
-            let solution = crate::day20::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(20, 2, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
+    fn highest_address() {
+        assert_eq!(part_1(vec![Range { from: 0, to: 8 }], 9).unwrap(), 9);
+        assert!(part_1(vec![Range { from: 0, to: 9 }], 9).is_err());
+        assert_eq!(part_1(vec![Range { from: 0, to: u32::MAX - 1 }], u32::MAX).unwrap(), u32::MAX);
     }
 }
@@ -1,63 +1,141 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 21,
+    name: "Scrambled Letters and Hash",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, _input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let mut start = parse_extra_param(extra_param);
-        let operations = parse_operations(&_input);
-        operations.iter().for_each(|op| op.execute(&mut start));
-        String::from_utf8(start).unwrap()
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let operations = timing::parse(|| parse(input))?;
+        let password = part_1(&operations, params.text("password")).map_err(|err| quote_line(err, input))?;
+        Ok(password.into())
+    }
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let operations = timing::parse(|| parse(input))?;
+        let password = part_2(&operations, params.text("password")).map_err(|err| quote_line(err, input))?;
+        Ok(password.into())
     }
-    fn part_2(&self, _input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let mut start = parse_extra_param(extra_param);
-        let operations = parse_operations(&_input);
-        operations.iter().rev().for_each(|op| op.undo(&mut start));
-        String::from_utf8(start).unwrap()
+    fn params(&self) -> Vec<Param> {
+        vec![Param::new(
+            "password",
+            Kind::Text,
+            ["abcdefgh", "fbgdceah"],
+            "password to scramble in part 1, scrambled password in part 2",
+        )]
     }
 }
 
-fn parse_extra_param(extra_param: Option<Box<dyn Any>>) -> Vec<u8> {
-    let s = *extra_param
-        .or(Some(Box::new("abcdefgh")))
-        .unwrap()
-        .downcast::<String>()
-        .unwrap();
-    s.as_bytes().to_vec()
+/// Scrambles `password` with the operations.
+pub fn part_1(operations: &[Box<dyn Operation>], password: &str) -> Result<String, PuzzleError> {
+    let mut start = check_password(password)?;
+    for (i, op) in operations.iter().enumerate() {
+        op.execute(&mut start).map_err(|err| failed(err, i))?;
+    }
+    Ok(String::from_utf8_lossy(&start).into_owned())
 }
 
-fn parse_operations(input: &str) -> Vec<Box<dyn Operation>> {
-    input
-        .lines()
-        .map(|line| parse(line))
-        .collect::<Vec<Box<dyn Operation>>>()
+/// Unscrambles `scrambled` by undoing the operations in reverse.
+pub fn part_2(operations: &[Box<dyn Operation>], scrambled: &str) -> Result<String, PuzzleError> {
+    let mut start = check_password(scrambled)?;
+    for (i, op) in operations.iter().enumerate().rev() {
+        op.undo(&mut start).map_err(|err| failed(err, i))?;
+    }
+    Ok(String::from_utf8_lossy(&start).into_owned())
+}
+
+fn check_password(password: &str) -> Result<Vec<u8>, PuzzleError> {
+    if password.is_empty() || !password.is_ascii() {
+        return Err(PuzzleError::new("the password must be a non-empty ASCII string"));
+    }
+    Ok(password.as_bytes().to_vec())
+}
+
+/// An operation that cannot be applied, pointing at its 1-based line.
+fn failed(err: String, i: usize) -> PuzzleError {
+    PuzzleError {
+        line: Some(i + 1),
+        ..PuzzleError::new(err)
+    }
 }
 
-fn parse(input: &str) -> Box<dyn Operation> {
+/// Adds the text of the line an error points at.
+fn quote_line(err: PuzzleError, input: &Input) -> PuzzleError {
+    match err.line {
+        Some(line) => {
+            let text = input.lines().nth(line - 1).unwrap_or_default().to_string();
+            err.with_text(&text)
+        }
+        None => err,
+    }
+}
+
+pub fn parse(input: &Input) -> Result<Vec<Box<dyn Operation>>, PuzzleError> {
+    error::parse_lines(input.lines(), parse_operation)
+}
+
+fn parse_operation(input: &str) -> Result<Box<dyn Operation>, String> {
     let words = input.split_whitespace().collect::<Vec<&str>>();
-    match words[0] {
-        "swap" => match words[1] {
-            "position" => SwapPosition::new(words),
-            "letter" => SwapLetter::new(words),
-            _ => panic!("Unknown swap operation"),
+    match words.first().copied() {
+        Some("swap") => match words.get(1).copied() {
+            Some("position") => SwapPosition::parse(words),
+            Some("letter") => SwapLetter::parse(words),
+            _ => Err(String::from("expected 'swap position' or 'swap letter'")),
         },
-        "rotate" => match words[1] {
-            "left" => RotateLeft::new(words),
-            "right" => RotateRight::new(words),
-            "based" => RotateBased::new(words),
-            _ => panic!("Unknown rotate operation"),
+        Some("rotate") => match words.get(1).copied() {
+            Some("left") => RotateLeft::parse(words),
+            Some("right") => RotateRight::parse(words),
+            Some("based") => RotateBased::parse(words),
+            _ => Err(String::from("expected 'rotate left', 'rotate right' or 'rotate based'")),
         },
-        "reverse" => Reverse::new(words),
-        "move" => Move::new(words),
-        _ => panic!("Unknown operation"),
+        Some("reverse") => Reverse::parse(words),
+        Some("move") => Move::parse(words),
+        _ => Err(String::from("unknown operation")),
+    }
+}
+
+fn number(s: &[&str], i: usize) -> Result<usize, String> {
+    error::number(s.get(i).copied(), "a position")
+}
+
+fn letter(s: &[&str], i: usize) -> Result<u8, String> {
+    match s.get(i).map(|word| word.as_bytes()) {
+        Some([letter]) => Ok(*letter),
+        _ => Err(String::from("expected a single letter")),
+    }
+}
+
+fn position(s: &[u8], letter: u8) -> Result<usize, String> {
+    s.iter()
+        .position(|&c| c == letter)
+        .ok_or_else(|| format!("letter '{}' is not in the password", letter as char))
+}
+
+fn check_positions(s: &[u8], positions: &[usize]) -> Result<(), String> {
+    match positions.iter().find(|&&position| position >= s.len()) {
+        Some(position) => Err(format!(
+            "position {} is outside the {} letter password",
+            position,
+            s.len()
+        )),
+        None => Ok(()),
     }
 }
 
-trait Operation {
-    fn execute(&self, s: &mut Vec<u8>);
-    fn undo(&self, s: &mut Vec<u8>);
+pub trait Operation {
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String>;
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String>;
 }
 
 struct SwapPosition {
@@ -66,21 +144,23 @@ struct SwapPosition {
 }
 
 impl SwapPosition {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(SwapPosition {
-            x: s[2].parse::<usize>().unwrap(),
-            y: s[5].parse::<usize>().unwrap(),
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        Ok(Box::new(SwapPosition {
+            x: number(&s, 2)?,
+            y: number(&s, 5)?,
+        }))
     }
 }
 
 impl Operation for SwapPosition {
-    fn execute(&self, s: &mut Vec<u8>) {
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        check_positions(s, &[self.x, self.y])?;
         s.swap(self.x, self.y);
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
-        self.execute(s);
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        self.execute(s)
     }
 }
 
@@ -90,23 +170,24 @@ struct SwapLetter {
 }
 
 impl SwapLetter {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(SwapLetter {
-            x: s[2].as_bytes()[0],
-            y: s[5].as_bytes()[0],
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        Ok(Box::new(SwapLetter {
+            x: letter(&s, 2)?,
+            y: letter(&s, 5)?,
+        }))
     }
 }
 
 impl Operation for SwapLetter {
-    fn execute(&self, s: &mut Vec<u8>) {
-        let x = s.iter().position(|&c| c == self.x).unwrap();
-        let y = s.iter().position(|&c| c == self.y).unwrap();
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        let x = position(s, self.x)?;
+        let y = position(s, self.y)?;
         s.swap(x, y);
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
-        self.execute(s);
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        self.execute(s)
     }
 }
 
@@ -115,20 +196,24 @@ struct RotateLeft {
 }
 
 impl RotateLeft {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(RotateLeft {
-            steps: s[2].parse::<usize>().unwrap(),
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        Ok(Box::new(RotateLeft {
+            steps: number(&s, 2)?,
+        }))
     }
 }
 
 impl Operation for RotateLeft {
-    fn execute(&self, s: &mut Vec<u8>) {
-        s.rotate_left(self.steps);
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        let len = s.len();
+        s.rotate_left(self.steps % len);
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
-        s.rotate_right(self.steps);
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        let len = s.len();
+        s.rotate_right(self.steps % len);
+        Ok(())
     }
 }
 
@@ -137,20 +222,24 @@ struct RotateRight {
 }
 
 impl RotateRight {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(RotateRight {
-            steps: s[2].parse::<usize>().unwrap(),
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        Ok(Box::new(RotateRight {
+            steps: number(&s, 2)?,
+        }))
     }
 }
 
 impl Operation for RotateRight {
-    fn execute(&self, s: &mut Vec<u8>) {
-        s.rotate_right(self.steps);
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        let len = s.len();
+        s.rotate_right(self.steps % len);
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
-        s.rotate_left(self.steps);
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        let len = s.len();
+        s.rotate_left(self.steps % len);
+        Ok(())
     }
 }
 
@@ -159,33 +248,41 @@ struct RotateBased {
 }
 
 impl RotateBased {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(RotateBased {
-            x: s[6].as_bytes()[0],
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        Ok(Box::new(RotateBased {
+            x: letter(&s, 6)?,
+        }))
     }
 }
 
 impl Operation for RotateBased {
-    fn execute(&self, s: &mut Vec<u8>) {
-        let x = s.iter().position(|&c| c == self.x).unwrap();
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        let x = position(s, self.x)?;
         let steps = 1 + x + if x >= 4 { 1 } else { 0 };
         let len = s.len();
         s.rotate_right(steps % len);
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
         s.rotate_left(1);
         let mut rotates = 0;
-        let mut pos = s.iter().position(|&c| c == self.x).unwrap();
+        let mut pos = position(s, self.x)?;
         while pos != rotates {
+            if rotates > s.len() {
+                return Err(format!(
+                    "rotating based on '{}' cannot be undone on this password",
+                    self.x as char
+                ));
+            }
             s.rotate_left(1);
             rotates += 1;
             if rotates == 4 {
                 s.rotate_left(1);
             }
-            pos = s.iter().position(|&c| c == self.x).unwrap();
+            pos = position(s, self.x)?;
         }
+        Ok(())
     }
 }
 
@@ -195,21 +292,24 @@ struct Reverse {
 }
 
 impl Reverse {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(Reverse {
-            x: s[2].parse::<usize>().unwrap(),
-            y: s[4].parse::<usize>().unwrap(),
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        let (x, y) = (number(&s, 2)?, number(&s, 4)?);
+        if x > y {
+            return Err(format!("cannot reverse from position {} back to {}", x, y));
+        }
+        Ok(Box::new(Reverse { x, y }))
     }
 }
 
 impl Operation for Reverse {
-    fn execute(&self, s: &mut Vec<u8>) {
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        check_positions(s, &[self.y])?;
         s[self.x..=self.y].reverse();
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
-        self.execute(s);
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        self.execute(s)
     }
 }
 
@@ -219,55 +319,26 @@ struct Move {
 }
 
 impl Move {
-    fn new(s: Vec<&str>) -> Box<dyn Operation> {
-        Box::new(Move {
-            from: s[2].parse::<usize>().unwrap(),
-            to: s[5].parse::<usize>().unwrap(),
-        })
+    fn parse(s: Vec<&str>) -> Result<Box<dyn Operation>, String> {
+        Ok(Box::new(Move {
+            from: number(&s, 2)?,
+            to: number(&s, 5)?,
+        }))
     }
 }
 
 impl Operation for Move {
-    fn execute(&self, s: &mut Vec<u8>) {
+    fn execute(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        check_positions(s, &[self.from, self.to])?;
         let c = s.remove(self.from);
         s.insert(self.to, c);
+        Ok(())
     }
 
-    fn undo(&self, s: &mut Vec<u8>) {
+    fn undo(&self, s: &mut Vec<u8>) -> Result<(), String> {
+        check_positions(s, &[self.from, self.to])?;
         let c = s.remove(self.to);
         s.insert(self.from, c);
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use std::any::Any;
-
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "decab", Some(Box::new(String::from("abcde")))),
-            (Data::Real, "bfheacgd", Some(Box::new(String::from("abcdefgh"))))
-        ];
-        for case in cases {
-            let solution = crate::day21::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(21, 1, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Real, "gcehdbfa", Some(Box::new(String::from("fbgdceah"))))
-        ];
-        for case in cases {
-            let solution = crate::day21::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(21, 2, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
+        Ok(())
     }
 }
@@ -1,41 +1,78 @@
-use std::{any::Any, collections::HashMap};
-
-use crate::common;
-use pathfinding;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    grid::{Grid, Pos},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    search,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 22,
+    name: "Grid Computing",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let nodes = parse_data(_input);
-        let viable_pairs = count_viable_pairs(&nodes);
-        viable_pairs.to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let nodes = timing::parse(|| parse(input))?;
+        Ok(part_1(&nodes).into())
     }
-    fn part_2(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let nodes = parse_data(_input);
-        let max_x = nodes.iter().map(|(coord, _)| coord.x).max().unwrap();
-        let path = shortest_path(&nodes);
-        let steps = path + 1 + (max_x - 1) * 5;
-        steps.to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let nodes = timing::parse(|| parse(input))?;
+        Ok(part_2(&nodes)?.into())
     }
 }
 
-fn parse_data(input: String) -> Grid {
-    let mut grid = Grid::new();
-    for line in input.lines().skip(2) {
-        let (coord, data) = Data::parse(line);
-        grid.insert(coord, data);
+/// How many pairs of nodes could move the data of one onto the other.
+pub fn part_1(grid: &Grid<Data>) -> usize {
+    count_viable_pairs(grid)
+}
+
+/// The fewest moves that bring the data of the top right node to the top left one.
+pub fn part_2(grid: &Grid<Data>) -> Result<usize, PuzzleError> {
+    if grid.width() < 2 {
+        return Err(PuzzleError::new("the grid must be at least two nodes wide"));
     }
-    grid
+    let path = shortest_path(grid)?;
+    Ok(path + 1 + (grid.width() - 2) * 5)
 }
 
-fn count_viable_pairs(grid: &Grid) -> usize {
+/// Reads the `df` listing, skipping its two header lines.
+pub fn parse(input: &Input) -> Result<Grid<Data>, PuzzleError> {
+    let mut nodes = vec![];
+    for (i, line) in input.lines().enumerate().skip(2) {
+        nodes.push(Data::parse(line).map_err(|err| PuzzleError::new(err).at_line(i + 1, line))?);
+    }
+    let width = nodes.iter().map(|(pos, _)| pos.x + 1).max().unwrap_or_default();
+    let height = nodes.iter().map(|(pos, _)| pos.y + 1).max().unwrap_or_default();
+    let mut grid = Grid::new(width, height, None);
+    for (pos, data) in &nodes {
+        grid[*pos] = Some(*data);
+    }
+    if grid.iter().any(|(_, data)| data.is_none()) || nodes.len() != width * height {
+        return Err(PuzzleError::new(format!(
+            "the grid has {} nodes, expected {}x{}",
+            nodes.len(),
+            width,
+            height
+        )));
+    }
+    Ok(grid.map(|data| data.unwrap()))
+}
+
+fn count_viable_pairs(grid: &Grid<Data>) -> usize {
     let mut count = 0;
-    for (coord, data) in grid {
+    for (coord, data) in grid.iter() {
         if data.used == 0 {
             continue;
         }
-        for (other_coord, other_data) in grid {
+        for (other_coord, other_data) in grid.iter() {
             if coord == other_coord {
                 continue;
             }
@@ -48,118 +85,61 @@ fn count_viable_pairs(grid: &Grid) -> usize {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
-impl Coord {
-    fn new(x: usize, y: usize) -> Self {
-        Coord { x, y }
-    }
-
-    fn manhattan_distance(&self, other: &Coord) -> usize {
-        (self.x as isize - other.x as isize).abs() as usize
-            + (self.y as isize - other.y as isize).abs() as usize
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Data {
-    used: usize,
-    available: usize,
+pub struct Data {
+    pub used: usize,
+    pub available: usize,
 }
 
 impl Data {
-    fn parse(s: &str) -> (Coord, Self) {
-        let mut words = s.split_whitespace().filter(|word| *word != "");
-        let mut parts = words.next().unwrap().split('-');
-        let x = parts.nth(1).unwrap().trim_start_matches('x').parse().unwrap();
-        let y = parts.next().unwrap().trim_start_matches('y').parse().unwrap();
-        let coord = Coord { x, y };
-        let used = words.nth(1).unwrap().trim_end_matches('T').parse().unwrap();
-        let available = words.next().unwrap().trim_end_matches('T').parse().unwrap();
+    fn parse(s: &str) -> Result<(Pos, Self), String> {
+        let mut words = s.split_whitespace().filter(|word| !word.is_empty());
+        let mut parts = words.next().ok_or("missing node name")?.split('-');
+        let x = error::number(parts.nth(1).map(|x| x.trim_start_matches('x')), "an x coordinate")?;
+        let y = error::number(parts.next().map(|y| y.trim_start_matches('y')), "a y coordinate")?;
+        let coord = Pos::new(x, y);
+        let used = error::number(words.nth(1).map(|used| used.trim_end_matches('T')), "the used size")?;
+        let available = error::number(
+            words.next().map(|available| available.trim_end_matches('T')),
+            "the available size",
+        )?;
         let data = Data { used, available };
-        (coord, data)
+        Ok((coord, data))
     }
 }
 
-type Grid = HashMap<Coord, Data>;
-
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 struct Node {
-    coord: Coord,
+    coord: Pos,
     data: Data,
 }
 
 impl Node {
-    fn neighbors(&self, grid: &Grid, max_x: usize, max_y: usize) -> Vec<(Node, usize)> {
-        let mut neighbors: Vec<(Node, usize)> = Vec::new();
-        if self.coord.x != 0 {
-            let c = Coord::new(self.coord.x - 1, self.coord.y);
-            neighbors.push((Node{coord: c.clone(), data: grid[&c]}, 1));
-        }
-        if self.coord.y != 0 {
-            let c = Coord::new(self.coord.x, self.coord.y - 1);
-            neighbors.push((Node{coord: c.clone(), data: grid[&c]}, 1));
-        }
-        if self.coord.x != max_x {
-            let c = Coord::new(self.coord.x + 1, self.coord.y);
-            neighbors.push((Node{coord: c.clone(), data: grid[&c]}, 1));
-        }
-        if self.coord.y != max_y {
-            let c = Coord::new(self.coord.x, self.coord.y + 1);
-            neighbors.push((Node{coord: c.clone(), data: grid[&c]}, 1));
-        }
-        neighbors = neighbors.iter().filter(|(n, _)| n.data.used <= 100).map(|x| *x).collect();
-        neighbors
+    fn neighbors(&self, grid: &Grid<Data>) -> Vec<(Node, usize)> {
+        grid.neighbours4(self.coord)
+            .map(|coord| Node { coord, data: grid[coord] })
+            .filter(|node| node.data.used <= 100)
+            .map(|node| (node, 1))
+            .collect()
     }
 }
 
-fn shortest_path(grid: &Grid) -> usize {
-    let empty = grid.iter().find(|(_, data)| data.used == 0).unwrap();
+fn shortest_path(grid: &Grid<Data>) -> Result<usize, PuzzleError> {
+    let coord = grid
+        .position(|data| data.used == 0)
+        .ok_or_else(|| PuzzleError::new("there is no empty node"))?;
     let start = Node {
-        coord: *empty.0,
-        data: *empty.1,
+        coord,
+        data: grid[coord],
     };
-    let max_x = grid.iter().map(|(coord, _)| coord.x).max().unwrap();
-    let max_y = grid.iter().map(|(coord, _)| coord.y).max().unwrap();
-    let goal = Coord::new(max_x - 1, 0);
-    let result = pathfinding::astar(&start,
-    |n: &Node| n.neighbors(grid, max_x, max_y),
-    |n: &Node| n.coord.manhattan_distance(&goal),
-    |n: &Node| n.coord == goal);
-    result.unwrap().1
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "7"),
-            (Data::Real, "1034")
-        ];
-        for case in cases {
-            let solution = crate::day22::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(22, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "7"),
-            (Data::Real, "261")
-        ];
-        for case in cases {
-            let solution = crate::day22::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(22, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    let goal = Pos::new(grid.width() - 2, 0);
+    let search = search::astar(
+        start,
+        |n: &Node| n.neighbors(grid),
+        |n: &Node| n.coord.manhattan(goal),
+        |n: &Node| n.coord == goal,
+    );
+    match search.path {
+        Some(path) => Ok(path.cost),
+        None => Err(PuzzleError::new("the empty node cannot reach the goal data")),
     }
 }
@@ -1,109 +1,66 @@
-use std::{any::Any, collections::{HashMap, HashSet, VecDeque}, vec, hash::Hash};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
 
 use itertools::Itertools;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    grid::{Grid, Pos},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    search,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 24,
+    name: "Air Duct Spelunking",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let graph = create_graph(&input);
-        let shortest_path = find_shortest_full_path(graph, false);
-        shortest_path.to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(&timing::parse(|| parse(input))?)?.into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let graph = create_graph(&input);
-        let shortest_path = find_shortest_full_path(graph, true);
-        shortest_path.to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(&timing::parse(|| parse(input))?)?.into())
     }
 }
 
-enum Direction {
-    Up,
-    Right, 
-    Down,
-    Left,
+/// The fewest steps that visit every numbered location, starting at 0.
+pub fn part_1(graph: &[Node]) -> Result<u32, PuzzleError> {
+    find_shortest_full_path(graph, false)
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
-struct Coordinate {
-    i: i32,
-    j: i32,
+/// The fewest steps that visit every numbered location and return to 0.
+pub fn part_2(graph: &[Node]) -> Result<u32, PuzzleError> {
+    find_shortest_full_path(graph, true)
 }
 
-impl Coordinate {
-    fn new(i: i32, j: i32) -> Coordinate {
-        Coordinate { i, j }
-    }
+type Map = Grid<char>;
 
-    fn neighbor(&self, direction: Direction) -> Coordinate {
-        match direction {
-            Direction::Up => Coordinate::new(self.i - 1, self.j),
-            Direction::Right => Coordinate::new(self.i, self.j + 1),
-            Direction::Down => Coordinate::new(self.i + 1, self.j),
-            Direction::Left => Coordinate::new(self.i, self.j - 1),
-        }
-    }
-
-    fn is_valid(&self, max_i: i32, max_j: i32) -> bool {
-        self.i >= 0 && self.j >= 0 && self.i < max_i && self.j < max_j
-    }
-}
-
-fn find_node(input: &String, node: char) -> Coordinate {
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            if c == node {
-                return Coordinate::new(i as i32, j as i32);
-            }
-        }
-    }
-    panic!("Node not found");
-}
-
-fn find_shortest_paths(input: &String, start: Coordinate) -> HashMap<Coordinate, i32> {
-    let mut paths = HashMap::new();
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-    let max_i = input.lines().count() as i32;
-    let max_j = input.lines().nth(0).unwrap().chars().count() as i32;
-    queue.push_back((start, 0));
-    while !queue.is_empty() {
-        let (current, current_distance) = queue.pop_front().unwrap();
-        if visited.contains(&current) {
-            continue;
-        }
-        for direction in vec![Direction::Up, Direction::Right, Direction::Down, Direction::Left] {
-            let neighbor = current.neighbor(direction);
-            if !neighbor.is_valid(max_i, max_j) || visited.contains(&neighbor) {
-                continue;
-            }
-            let neighbor_char = input.lines().nth(neighbor.i as usize).unwrap().chars().nth(neighbor.j as usize).unwrap();
-            if neighbor_char == '#' {
-                continue;
-            }
-            if neighbor_char.is_digit(10) {
-                if paths.contains_key(&neighbor) {
-                    let dist = paths.get(&neighbor).unwrap();
-                    if *dist > current_distance + 1 {
-                        paths.insert(neighbor, current_distance + 1);
-                    }
-                } else {
-                    paths.insert(neighbor, current_distance + 1);
-                }
-            }
-            queue.push_back((neighbor, current_distance + 1));
-        }
-        visited.insert(current);
-    }
-    paths
+fn find_shortest_paths(map: &Map, start: Pos) -> HashMap<Pos, u32> {
+    let open = |pos: &Pos| map.neighbours4(*pos).filter(|neighbor| map[*neighbor] != '#');
+    search::reachable(start, open, None)
+        .distances
+        .into_iter()
+        .filter(|(pos, distance)| *distance > 0 && map[*pos].is_ascii_digit())
+        .map(|(pos, distance)| (pos, distance as u32))
+        .collect()
 }
 
+/// A numbered location with the distances to the locations it reaches, by number.
 #[derive(Debug)]
-struct Node {
-    coordinate: Coordinate,
-    neighbors: HashMap<i32, i32>
+pub struct Node {
+    coordinate: Pos,
+    pub neighbors: HashMap<i32, u32>
 }
 
 impl Hash for Node {
@@ -121,93 +78,93 @@ impl PartialEq for Node {
 impl Eq for Node {}
 
 impl Node {
-    fn new(coordinate: Coordinate) -> Node {
+    fn new(coordinate: Pos) -> Node {
         Node {
             coordinate,
             neighbors: HashMap::new()
         }
     }
 
-    fn add_neighbor(&mut self, neighbor_value: i32, distance: i32) {
+    fn add_neighbor(&mut self, neighbor_value: i32, distance: u32) {
         self.neighbors.insert(neighbor_value, distance);
     }
 }
 
-fn create_graph(input: &String) -> Vec<Node> {
+/// Reads the map and finds the distances between its numbered locations, indexed by number.
+pub fn parse(input: &Input) -> Result<Vec<Node>, PuzzleError> {
+    let map = Grid::parse(input.lines(), Ok)?;
     let mut nodes = Vec::new();
-    for i in 0..number_of_nodes(input) {
-        let start = find_node(&input, i.to_string().chars().nth(0).unwrap());
-        let paths = find_shortest_paths(&input, start);
+    for start in locations(input, &map)? {
+        let paths = find_shortest_paths(&map, start);
         let mut start_node = Node::new(start);
         for (coordinate, distance) in paths {
-            let value = get_value(input, coordinate);
+            let value = get_value(&map, coordinate);
             start_node.add_neighbor(value, distance);
         }
         nodes.push(start_node);
     }
-    nodes
+    Ok(nodes)
 }
 
-fn get_value(input: &String, coordinate: Coordinate) -> i32 {
-    input.lines().nth(coordinate.i as usize).unwrap().chars().nth(coordinate.j as usize).unwrap().to_digit(10).unwrap() as i32
+fn get_value(map: &Map, coordinate: Pos) -> i32 {
+    map[coordinate].to_digit(10).unwrap() as i32
 }
 
-fn number_of_nodes(input: &String) -> i32 {
-    let mut count = 0;
-    for line in input.lines() {
-        for c in line.chars() {
-            if c.is_digit(10) {
-                count += 1;
-            }
+/// The positions of the numbered locations in order of their numbers, which must each
+/// appear once and run from 0 up without gaps.
+fn locations(input: &Input, map: &Map) -> Result<Vec<Pos>, PuzzleError> {
+    let mut locations = BTreeMap::new();
+    for (pos, c) in map.iter() {
+        let Some(digit) = c.to_digit(10) else {
+            continue;
+        };
+        if locations.insert(digit, pos).is_some() {
+            let line = input.lines().nth(pos.y).unwrap_or_default();
+            return Err(PuzzleError::new(format!("location {} is on the map more than once", digit))
+                .at_line(pos.y + 1, line));
         }
     }
-    count
+    if let Some(missing) = (0..).zip(locations.keys()).find(|(i, digit)| i != *digit) {
+        return Err(PuzzleError::new(format!("location {} is not on the map", missing.0)));
+    }
+    Ok(locations.into_values().collect())
 }
 
-fn find_shortest_full_path(graph: Vec<Node>, go_home: bool) -> i32 {
+fn find_shortest_full_path(graph: &[Node], go_home: bool) -> Result<u32, PuzzleError> {
     let values: Vec<i32> = graph.iter().enumerate().map(|(i, _)| i as i32).filter(|i| *i != 0).collect();
-    values.iter().permutations(values.len()).map(|permutation| {
-        let mut distance = *(&graph[0]).neighbors.get(permutation[0]).unwrap();
+    if values.is_empty() {
+        return Ok(0);
+    }
+    if let Some(unreachable) = values.iter().find(|value| !graph[0].neighbors.contains_key(value)) {
+        return Err(PuzzleError::new(format!("location {} cannot be reached from 0", unreachable)));
+    }
+    let shortest = values.iter().permutations(values.len()).map(|permutation| {
+        let mut distance = graph[0].neighbors[permutation[0]];
         for i in 0..permutation.len() - 1 {
             let current = &graph[*permutation[i] as usize];
             let next = permutation[i + 1];
-            distance += current.neighbors.get(next).expect(&next.to_string());
+            distance += current.neighbors[next];
         }
         if go_home {
-            distance += graph[0].neighbors.get(permutation[permutation.len() - 1]).unwrap();
+            distance += graph[0].neighbors[permutation[permutation.len() - 1]];
         }
         distance
-    }).min().unwrap()
+    }).min();
+    Ok(shortest.unwrap_or_default())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "14"),
-            (Data::Real, "460"),
-        ];
-        for case in cases {
-            let solution = crate::day24::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(24, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+    use crate::{common, input::Input};
 
     #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "20"),
-            (Data::Real, "668"),
-        ];
-        for case in cases {
-            let solution = crate::day24::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(24, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    fn malformed_input() {
+        let input = Input::new("#####\n#0.1#\n#1..#\n#####");
+        let err = common::solve_part(24, 1, &input, &[]).unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.to_string(), "day 24, line 3: location 1 is on the map more than once\n  | #1..#");
+        let input = Input::new("#####\n#0.2#\n#####");
+        let err = common::solve_part(24, 1, &input, &[]).unwrap_err();
+        assert_eq!(err.to_string(), "day 24: location 1 is not on the map");
     }
 }
@@ -1,59 +1,95 @@
-use std::any::Any;
+use std::collections::HashMap;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    assembunny::{self, Computer, Instruction, Registers},
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
+
+/// Instructions a candidate may run between two outputs before it is given up on.
+const STEP_LIMIT: u64 = 1_000_000;
+/// Outputs a candidate may produce without repeating a state before it is given up on.
+const OUTPUT_LIMIT: usize = 10_000;
+/// Largest initial value of register a that is tried.
+const SEARCH_LIMIT: i64 = 1 << 20;
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 25,
+    name: "Clock Signal",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let magic_constant = get_magic_constant(_input);
-        let mut start = 1;
-        let mut odd = true;
-        while start < magic_constant {
-            if odd {
-                start *= 2;
-                odd = false;
-            } else {
-                start = start * 2 + 1;
-                odd = true;
-            }
-        }
-        (start - magic_constant).to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let program = timing::parse(|| parse(input))?;
+        Ok(part_1(&program)?.into())
     }
-    fn part_2(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        String::new()
+    fn part_2(&self, _input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(Answer::NoAnswer)
     }
 }
 
-fn get_magic_constant(input: String) -> i32 {
-    let x = input.lines().nth(1).unwrap().split(" ").nth(1).unwrap().parse::<i32>().unwrap();
-    let y = input.lines().nth(2).unwrap().split(" ").nth(1).unwrap().parse::<i32>().unwrap();
-    x * y
+/// Parses the assembunny program.
+pub fn parse(input: &Input) -> Result<Vec<Instruction>, PuzzleError> {
+    assembunny::parse_program(input.text())
 }
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
 
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Real, "192")
-        ];
-        for case in cases {
-            let solution = crate::day25::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(25, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
+/// The lowest initial value of register a that makes the program output a clock signal.
+pub fn part_1(program: &[Instruction]) -> Result<i64, PuzzleError> {
+    for a in 1..=SEARCH_LIMIT {
+        if is_clock_signal(program, a) {
+            return Ok(a);
         }
     }
+    Err(PuzzleError::new(format!(
+        "no initial value up to {} produces a clock signal",
+        SEARCH_LIMIT
+    )))
+}
 
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![];
-        for case in cases {
-            let solution = crate::day25::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(25, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
+/// The whole machine state right after an `out`, including the program as `tgl` may have changed it.
+type State = (i64, Registers, Vec<Instruction>);
+
+/// Runs the program with `a` in register a and checks that it outputs 0, 1, 0, 1... forever.
+/// Once the machine is back in a state it was in after an earlier output, everything
+/// in between repeats, so an even number of alternating outputs since then proves the signal.
+fn is_clock_signal(program: &[Instruction], a: i64) -> bool {
+    let mut computer = Computer::new(program.to_vec(), [a, 0, 0, 0]).optimized();
+    let mut seen: HashMap<State, usize> = HashMap::new();
+    for count in 0..OUTPUT_LIMIT {
+        match computer.next_output(STEP_LIMIT) {
+            Some(value) if value == (count % 2) as i64 => (),
+            _ => return false,
         }
+        let state = (computer.pc, computer.registers, computer.program.clone());
+        if let Some(previous) = seen.insert(state, count) {
+            return (count - previous) % 2 == 0;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_clock_signal;
+    use crate::assembunny::parse_program;
+
+    #[test]
+    fn clock_signal() {
+        let program = parse_program("cpy a b\ndec b\ndec b\nout b\ninc b\nout b\ndec b\njnz 1 -4").unwrap();
+        assert!(is_clock_signal(&program, 2));
+        assert!(!is_clock_signal(&program, 1));
+        assert!(!is_clock_signal(&program, 3));
+        let halting = parse_program("out 0\nout 1").unwrap();
+        assert!(!is_clock_signal(&halting, 1));
+        let odd_cycle = parse_program("out 0\nout 1\nout 0\njnz 1 -3").unwrap();
+        assert!(!is_clock_signal(&odd_cycle, 1));
     }
 }
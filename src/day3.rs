@@ -1,23 +1,34 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 3,
+    name: "Squares With Three Sides",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let triangles = create_triangles_by_row(input);
-        let solution = count_valid(triangles);
-        format! {"{}", solution}
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let triangles = timing::parse(|| parse_rows(input))?;
+        Ok(count_valid(&triangles).into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let triangles = create_triangles_by_col(input);
-        let solution = count_valid(triangles);
-        format! {"{}", solution}
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let triangles = timing::parse(|| parse_columns(input))?;
+        Ok(count_valid(&triangles).into())
     }
 }
 
-fn count_valid(triangles: Vec<Triangle>) -> usize {
+/// How many of the triangles are possible.
+pub fn count_valid(triangles: &[Triangle]) -> usize {
     triangles
         .iter()
         .filter(|triangle| triangle.is_valid())
@@ -25,85 +36,49 @@ fn count_valid(triangles: Vec<Triangle>) -> usize {
 }
 
 #[derive(Debug)]
-struct Triangle(i32, i32, i32);
+pub struct Triangle(pub i32, pub i32, pub i32);
 
 impl Triangle {
-    fn from(input: &str) -> Triangle {
-        let mut parts = input
-            .split("  ")
-            .map(|part| part.trim())
-            .filter(|part| part != &"")
-            .map(|part| part.parse::<i32>().unwrap());
-        Triangle(
-            parts.next().unwrap(),
-            parts.next().unwrap(),
-            parts.next().unwrap(),
-        )
-    }
-
-    fn is_valid(&self) -> bool {
+    pub fn is_valid(&self) -> bool {
         self.0 + self.1 > self.2 && self.0 + self.2 > self.1 && self.1 + self.2 > self.0
     }
 }
 
-fn create_triangles_by_row(input: String) -> Vec<Triangle> {
-    input
-        .split("\n")
-        .map(|line| Triangle::from(line))
-        .collect()
+/// Reads one triangle from every line.
+pub fn parse_rows(input: &Input) -> Result<Vec<Triangle>, PuzzleError> {
+    let rows = break_into_parts(input)?;
+    Ok(rows.iter().map(|row| Triangle(row[0], row[1], row[2])).collect())
 }
 
-fn break_into_parts(line: Option<&str>) -> Vec<i32> {
-    line.unwrap()
-        .split("  ")
-        .map(|part| part.trim())
-        .filter(|part| part != &"")
-        .map(|part| part.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>()
+fn break_into_parts(input: &Input) -> Result<Vec<[i32; 3]>, PuzzleError> {
+    input
+        .numbers::<i32>()?
+        .into_iter()
+        .zip(input.lines())
+        .enumerate()
+        .map(|(i, (row, line))| {
+            <[i32; 3]>::try_from(row).map_err(|row| {
+                PuzzleError::new(format!("expected three side lengths, got {}", row.len())).at_line(i + 1, line)
+            })
+        })
+        .collect()
 }
 
-fn create_triangles_by_col(input: String) -> Vec<Triangle> {
-    let mut lines = input.split("\n");
+/// Reads the triangles down the columns, three lines at a time.
+pub fn parse_columns(input: &Input) -> Result<Vec<Triangle>, PuzzleError> {
+    let rows = break_into_parts(input)?;
+    if !rows.len().is_multiple_of(3) {
+        return Err(PuzzleError::new(format!(
+            "the number of lines must be a multiple of 3, got {}",
+            rows.len()
+        )));
+    }
     let mut triangles = vec![];
-    while let Some(first) = lines.next() {
-        let second = lines.next();
-        let third = lines.next();
-        let first = break_into_parts(Some(first));
-        let second = break_into_parts(second);
-        let third = break_into_parts(third);
+    for group in rows.chunks(3) {
+        let [first, second, third] = [group[0], group[1], group[2]];
         for i in 0..3 {
             triangles.push(Triangle(first[i], second[i], third[i]));
         }
     }
-    triangles
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "0"),
-            (Data::Test(2), "3"),
-            (Data::Real, "1050"),
-        ];
-        for case in cases {
-            let solution = crate::day3::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(3, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Real, "1921")];
-        for case in cases {
-            let solution = crate::day3::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(3, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+    Ok(triangles)
 }
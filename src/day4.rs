@@ -1,65 +1,89 @@
-use std::{any::Any, collections::HashMap};
+use std::collections::HashMap;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 4,
+    name: "Security Through Obscurity",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let rooms = create_rooms(input);
-        format!(
-            "{}",
-            rooms
-                .iter()
-                .filter(|room| !room.is_decoy())
-                .map(|room| room.id)
-                .sum::<i32>()
-        )
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let rooms = timing::parse(|| parse(input))?;
+        Ok(part_1(&rooms).into())
     }
 
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let mut rooms = create_rooms(input);
-        rooms = rooms
-            .iter()
-            .filter(|room| !room.is_decoy())
-            .map(|room: &Room| -> Room { room.decrypt() })
-            .filter(|room| room.real_name.contains("north"))
-            .collect::<Vec<Room>>();
-        if let Some(room) = rooms.get(0) {
-            return room.id.to_string();
-        }
-        String::from("Not found")
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let rooms = timing::parse(|| parse(input))?;
+        Ok(part_2(&rooms).into())
     }
 }
 
-fn create_rooms(input: String) -> Vec<Room> {
-    input.split("\n").map(|line| Room::new(line)).collect()
+/// The sum of the sector ids of the real rooms.
+pub fn part_1(rooms: &[Room]) -> i32 {
+    rooms
+        .iter()
+        .filter(|room| !room.is_decoy())
+        .map(|room| room.id)
+        .sum::<i32>()
+}
+
+/// The sector id of the real room whose decrypted name mentions the North Pole.
+pub fn part_2(rooms: &[Room]) -> Option<i32> {
+    rooms
+        .iter()
+        .filter(|room| !room.is_decoy())
+        .map(|room: &Room| -> Room { room.decrypt() })
+        .find(|room| room.real_name.contains("north"))
+        .map(|room| room.id)
+}
+
+pub fn parse(input: &Input) -> Result<Vec<Room>, PuzzleError> {
+    error::parse_lines(input.lines(), Room::new)
 }
 
 #[derive(Debug)]
-struct Room {
-    encrypted_name: String,
-    real_name: String,
-    id: i32,
-    checksum: String,
+pub struct Room {
+    pub encrypted_name: String,
+    /// Empty until the room is decrypted.
+    pub real_name: String,
+    pub id: i32,
+    pub checksum: String,
 }
 
 impl Room {
-    fn new(mut line: &str) -> Room {
-        line = &line[..line.len() - 1];
-        let parts: Vec<&str> = line.split("[").collect();
-        let checksum = parts[1];
-        let parts: Vec<&str> = parts[0].split("-").collect();
-        let id: i32 = parts.last().unwrap().parse().unwrap();
-        let name = parts[..parts.len() - 1].join("-");
-        Room {
-            encrypted_name: name,
+    fn new(line: &str) -> Result<Room, String> {
+        let (name, checksum) = line
+            .strip_suffix(']')
+            .and_then(|line| line.split_once('['))
+            .ok_or("expected a room like 'name-123[abcde]'")?;
+        let (name, id) = name.rsplit_once('-').ok_or("missing sector id")?;
+        let id = error::number(Some(id), "a sector id")?;
+        if let Some(c) = name.chars().find(|c| !c.is_ascii_lowercase() && *c != '-') {
+            return Err(format!("room names may only contain a-z and '-', got '{}'", c));
+        }
+        if checksum.len() != 5 {
+            return Err(format!("checksum must have 5 letters, got '{}'", checksum));
+        }
+        Ok(Room {
+            encrypted_name: name.to_string(),
             real_name: String::new(),
-            id: id,
+            id,
             checksum: checksum.to_string(),
-        }
+        })
     }
-    fn is_decoy(&self) -> bool {
+    pub fn is_decoy(&self) -> bool {
         let mut letters: HashMap<char, i32> = HashMap::new();
         for c in self.encrypted_name.chars() {
             let count = letters.entry(c).or_insert(1);
@@ -75,12 +99,12 @@ impl Room {
             }
             b.1.cmp(a.1)
         });
-        let checksum: String = count_vec[0..5].iter().map(|pair| pair.0).collect();
+        let checksum: String = count_vec.iter().take(5).map(|pair| pair.0).collect();
         checksum != self.checksum
     }
 
-    fn decrypt(&self) -> Room {
-        let diff = 'z' as u8 - 'a' as u8 + 1;
+    pub fn decrypt(&self) -> Room {
+        let diff = b'z' - b'a' + 1;
         let number_of_rotations = (self.id % diff as i32) as u8;
         let mut name = String::new();
         for c in self.encrypted_name.chars() {
@@ -88,7 +112,7 @@ impl Room {
                 '-' => name += " ",
                 c => {
                     let mut c = (c as u8) + number_of_rotations;
-                    if c > 'z' as u8 {
+                    if c > b'z' {
                         c -= diff;
                     }
                     name += &(c as char).to_string();
@@ -106,26 +130,13 @@ impl Room {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "1514"), (Data::Real, "409147")];
-        for case in cases {
-            let solution = crate::day4::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(4, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+    use crate::{common, input::Input};
 
     #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(2), "Not found"), (Data::Real, "991")];
-        for case in cases {
-            let solution = crate::day4::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(4, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    fn malformed_input() {
+        let input = Input::new("aaaaa-bbb-z-y-x-123[abxyz]\nnot-a-réal-room-404[oarel]\n");
+        let err = common::solve_part(4, 2, &input, &[]).unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.message, "room names may only contain a-z and '-', got 'é'");
     }
 }
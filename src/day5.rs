@@ -1,39 +1,58 @@
-use std::any::Any;
-
-use crate::common;
-use md5;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 5,
+    name: "How About a Nice Game of Chess?",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let mut pwd = String::new();
-        let mut index = 0;
-        while pwd.len() < 8 {
-            if let Some((next, _)) = is_valid(format!("{}{}", input, index)) {
-                pwd += &next.to_string();
-                println!("{}", pwd);
-            }
-            index += 1;
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(input.trimmed()).into())
+    }
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(input.trimmed()).into())
+    }
+}
+
+/// The password made of the sixth hex digit of the first eight interesting hashes.
+pub fn part_1(door_id: &str) -> String {
+    let mut pwd = String::new();
+    let mut index = 0;
+    while pwd.len() < 8 {
+        if let Some((next, _)) = is_valid(format!("{}{}", door_id, index)) {
+            pwd += &next.to_string();
         }
-        pwd
+        index += 1;
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        let mut pwd: Vec<String> = vec!["-".to_string(); 8];
-        let mut index = 0;
-        while pwd.contains(&"-".to_string()) {
-            if let Some((pos, c)) = is_valid(format!("{}{}", input, index)) {
-                if pos >= '0' && pos <= '7' {
-                    let idx: usize = pos as usize - '0' as usize;
-                    if pwd[idx] == "-" {
-                        pwd[idx] = c.to_string();
-                    }
-                };
-            }
-            index += 1;
+    pwd
+}
+
+/// The password whose positions are given by the sixth hex digit of the interesting hashes.
+pub fn part_2(door_id: &str) -> String {
+    let mut pwd: Vec<String> = vec!["-".to_string(); 8];
+    let mut index = 0;
+    while pwd.contains(&"-".to_string()) {
+        if let Some((pos, c)) = is_valid(format!("{}{}", door_id, index)) {
+            if ('0'..='7').contains(&pos) {
+                let idx: usize = pos as usize - '0' as usize;
+                if pwd[idx] == "-" {
+                    pwd[idx] = c.to_string();
+                }
+            };
         }
-        pwd.join("")
+        index += 1;
     }
+    pwd.join("")
 }
 
 fn is_valid(pwd: String) -> Option<(char, char)> {
@@ -43,31 +62,3 @@ fn is_valid(pwd: String) -> Option<(char, char)> {
     }
     None
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    #[ignore]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "18f47a30"), (Data::Real, "f77a0e6e")];
-        for case in cases {
-            let solution = crate::day5::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(5, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    #[ignore]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "05ace8e3"), (Data::Real, "999828ec")];
-        for case in cases {
-            let solution = crate::day5::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(5, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
@@ -1,33 +1,55 @@
-use std::{any::Any, collections::HashMap};
+use std::collections::HashMap;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 6,
+    name: "Signals and Noise",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        get_most_occured(create_occurence_maps(input))
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(&timing::parse(|| parse(input))?).into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        get_least_occured(create_occurence_maps(input))
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(&timing::parse(|| parse(input))?).into())
     }
 }
 
-fn create_occurence_maps(input: String) -> Vec<HashMap<char, i32>> {
-    let lines: Vec<&str> = input.split("\n").collect();
-    let length = lines[0].len();
-    let lines = lines.iter();
+/// Counts how often every character occurs at every position of the messages.
+pub fn parse(input: &Input) -> Result<Vec<HashMap<char, i32>>, PuzzleError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let length = lines.first().map_or(0, |line| line.chars().count());
+    if length == 0 {
+        return Err(PuzzleError::new("the first message is empty"));
+    }
     let mut result: Vec<HashMap<char, i32>> = vec![HashMap::new(); length];
-    lines.for_each(|line| {
-        line.char_indices().for_each(|(i, c)| {
+    for (number, line) in lines.iter().enumerate() {
+        if line.chars().count() != length {
+            return Err(PuzzleError::new(format!("every message must have {} characters", length))
+                .at_line(number + 1, line));
+        }
+        line.chars().enumerate().for_each(|(i, c)| {
             let count = result[i].entry(c).or_insert(1);
             *count += 1;
         })
-    });
-    result
+    }
+    Ok(result)
 }
 
-fn get_most_occured(occ: Vec<HashMap<char, i32>>) -> String {
+/// The message made of the most common character at every position.
+pub fn part_1(occ: &[HashMap<char, i32>]) -> String {
     occ.iter()
         .map(|m| {
             m.iter()
@@ -38,7 +60,8 @@ fn get_most_occured(occ: Vec<HashMap<char, i32>>) -> String {
         .collect()
 }
 
-fn get_least_occured(occ: Vec<HashMap<char, i32>>) -> String {
+/// The message made of the least common character at every position.
+pub fn part_2(occ: &[HashMap<char, i32>]) -> String {
     occ.iter()
         .map(|m| {
             m.iter()
@@ -51,26 +74,14 @@ fn get_least_occured(occ: Vec<HashMap<char, i32>>) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
+    use crate::{common, input::Input};
 
     #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "easter"), (Data::Real, "xdkzukcf")];
-        for case in cases {
-            let solution = crate::day6::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(6, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "advent"), (Data::Real, "cevsgyvd")];
-        for case in cases {
-            let solution = crate::day6::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(6, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    fn malformed_input() {
+        let input = Input::new("eedadn\ndrvtee\neandsr\nraa\n");
+        let err = common::solve_part(6, 1, &input, &[]).unwrap_err();
+        assert_eq!(err.line, Some(4));
+        assert_eq!(err.text.as_deref(), Some("raa"));
+        assert_eq!(err.to_string(), "day 6, line 4: every message must have 6 characters\n  | raa");
     }
 }
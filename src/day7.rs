@@ -1,33 +1,52 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 7,
+    name: "Internet Protocol Version 7",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        input
-            .split("\n")
-            .filter(|line| support_tls(line))
-            .count()
-            .to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(&timing::parse(|| parse(input))).into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        input
-            .split("\n")
-            .filter(|line| support_ssl(line))
-            .count()
-            .to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(&timing::parse(|| parse(input))).into())
     }
 }
 
-fn support_tls(line: &str) -> bool {
+/// How many of the addresses support TLS.
+pub fn part_1(addresses: &[&str]) -> usize {
+    addresses.iter().filter(|address| support_tls(address)).count()
+}
+
+/// How many of the addresses support SSL.
+pub fn part_2(addresses: &[&str]) -> usize {
+    addresses.iter().filter(|address| support_ssl(address)).count()
+}
+
+/// The addresses, one per line.
+pub fn parse(input: &Input) -> Vec<&str> {
+    input.lines().collect()
+}
+
+pub fn support_tls(line: &str) -> bool {
     let line = line.replace("[", "-?").replace("]", "-");
     let words = line.split("-");
     let mut has_abba = false;
     for word in words {
-        if word.starts_with("?") {
-            if !check_hypernet(&word[1..]) {
+        if let Some(hypernet) = word.strip_prefix('?') {
+            if !check_hypernet(hypernet) {
                 return false;
             }
         } else {
@@ -60,14 +79,14 @@ fn is_abba(input: &[char]) -> bool {
     input[0] == input[3] && input[1] == input[2] && input[0] != input[1]
 }
 
-fn support_ssl(line: &str) -> bool {
+pub fn support_ssl(line: &str) -> bool {
     let line = line.replace("[", "-?").replace("]", "-");
     let words = line.split("-");
     let mut supernet: Vec<&str> = vec![];
     let mut hypernet: Vec<&str> = vec![];
     words.for_each(|word| {
-        if word.starts_with("?") {
-            hypernet.push(&word[1..]);
+        if let Some(word) = word.strip_prefix('?') {
+            hypernet.push(word);
         } else {
             supernet.push(word);
         }
@@ -110,29 +129,3 @@ fn get_abas(words: Vec<&str>) -> Vec<String> {
 fn is_aba(input: &[char]) -> bool {
     input[0] == input[2] && input[0] != input[1]
 }
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(1), "2"), (Data::Real, "118")];
-        for case in cases {
-            let solution = crate::day7::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(7, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![(Data::Test(2), "3"), (Data::Real, "260")];
-        for case in cases {
-            let solution = crate::day7::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(7, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
-}
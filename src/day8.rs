@@ -1,122 +1,166 @@
-use std::{any::Any, fmt::Display};
-
-use crate::common;
+use std::fmt::Display;
+
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    grid::{Grid, Pos},
+    image,
+    input::Input,
+    ocr::FONT_5X6,
+    params::{Kind, Param, Params},
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
-const DEFAULT_SIZE: Size = Size{row: 6, col: 50};
+pub const ENTRY: Entry = Entry {
+    day: 8,
+    name: "Two-Factor Authentication",
+    puzzle: &Puzzle {},
+};
 
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let size: Size = match extra_param {
-            Some(b) => (*b.downcast_ref::<Size>().unwrap()).clone(),
-            None => DEFAULT_SIZE,
-        };
-        let mut screen = Screen::new(size);
-        let instructions = create_instructions(input);
-        for instruction in instructions {
-            (*instruction).execute(&mut screen);
-        }
-        screen.count().to_string()
-    }
-    fn part_2(&self, input: String, extra_param: Option<Box<dyn Any>>) -> String {
-        let size: Size = match extra_param {
-            Some(b) => (*b.downcast_ref::<Size>().unwrap()).clone(),
-            None => DEFAULT_SIZE,
-        };
-        let mut screen = Screen::new(size);
-        let instructions = create_instructions(input);
-        for instruction in instructions {
-            (*instruction).execute(&mut screen);
+    fn part_1(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let size = Size::from_params(params);
+        let instructions = timing::parse(|| parse(input, size))?;
+        Ok(part_1(size, &instructions).into())
+    }
+    fn part_2(&self, input: &Input, params: &Params) -> Result<Answer, PuzzleError> {
+        let size = Size::from_params(params);
+        let instructions = timing::parse(|| parse(input, size))?;
+        let screen = part_2(size, &instructions);
+        // Screens of another height, like the one of the example, cannot hold letters.
+        if screen.display.height() != FONT_5X6.height {
+            return Ok(Answer::Grid(screen.rows()));
         }
-        println!("{}", screen);
-        String::new()
+        Ok(FONT_5X6.read(&screen.display).map_err(PuzzleError::new)?.into())
+    }
+    fn params(&self) -> Vec<Param> {
+        let kind = Kind::Int { min: 1, max: 1000 };
+        vec![
+            Param::new("rows", kind, ["6", "6"], "number of pixel rows of the screen"),
+            Param::new("cols", kind, ["50", "50"], "number of pixel columns of the screen"),
+        ]
+    }
+}
+
+/// How many pixels lit up after the instructions.
+pub fn part_1(size: Size, instructions: &[Box<dyn Instruction>]) -> usize {
+    part_2(size, instructions).count()
+}
+
+/// The screen after the instructions.
+pub fn part_2(size: Size, instructions: &[Box<dyn Instruction>]) -> Screen {
+    let mut screen = Screen::new(size);
+    for instruction in instructions {
+        instruction.execute(&mut screen);
+    }
+    screen
+}
+
+/// The blank screen followed by the screen after every instruction.
+pub fn frames(size: Size, instructions: &[Box<dyn Instruction>]) -> Vec<Screen> {
+    let mut screen = Screen::new(size);
+    let mut frames = vec![screen.clone()];
+    for instruction in instructions {
+        instruction.execute(&mut screen);
+        frames.push(screen.clone());
     }
+    frames
 }
 
 #[derive(Clone, Copy)]
-struct Size {
-    row: usize, 
-    col: usize
+pub struct Size {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Size {
+    pub fn from_params(params: &Params) -> Size {
+        Size {
+            row: params.int("rows") as usize,
+            col: params.int("cols") as usize,
+        }
+    }
 }
 
-struct Screen {
-    display: Vec<Vec<bool>>,
+#[derive(Clone)]
+pub struct Screen {
+    pub display: Grid<bool>,
 }
 
 impl Screen {
-    fn new(size: Size) -> Screen {
+    pub fn new(size: Size) -> Screen {
         Screen {
-            display: vec![vec![false; size.col]; size.row],
+            display: Grid::new(size.col, size.row, false),
         }
     }
 
     fn rect(&mut self, rect: &Rect) {
         for i in 0..rect.b {
             for j in 0..rect.a {
-                self.display[i][j] = true;
+                self.display[Pos::new(j, i)] = true;
             }
         }
     }
 
     fn rotate_row(&mut self, rotate: &RotateRow) {
-        self.display[rotate.row].rotate_right(rotate.amount);
+        self.display.rotate_row(rotate.row, rotate.amount);
     }
 
     fn rotate_column(&mut self, rotate: &RotateColumn) {
-        let mut original = vec![];
-        for line in &self.display {
-            original.push(line[rotate.col]);
-        }
-        original.rotate_right(rotate.amount);
-        for (i, value) in original.iter().enumerate() {
-            self.display[i][rotate.col] = *value;
-        }
+        self.display.rotate_column(rotate.col, rotate.amount);
+    }
+
+    /// The screen drawn with `#` for lit and `.` for dark pixels, one string per row.
+    pub fn rows(&self) -> Vec<String> {
+        self.display.render(|pixel| if *pixel { '#' } else { '.' })
+    }
+
+    pub fn to_pbm(&self) -> String {
+        image::pbm(&self.display)
     }
 
-    fn count(&self) -> usize {
-        self.display
-            .iter()
-            .map(|line| line.iter().filter(|value| **value).count())
-            .sum()
+    /// The screen as a PNG image with every pixel `scale` image pixels wide.
+    pub fn to_png(&self, scale: usize) -> Vec<u8> {
+        image::png(&self.display, scale)
+    }
+
+    pub fn count(&self) -> usize {
+        self.display.iter().filter(|(_, pixel)| **pixel).count()
     }
 }
 
 impl Display for Screen {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut lines: Vec<String> = vec![];
-        for row in &self.display {
-            let mut line = String::new();
-            for pixel in row {
-                match pixel {
-                    true => line += "#",
-                    false => line += ".",
-                }
-            }
-            lines.push(line)
-        }
-        write!(f, "{}", lines.join("\n"))
+        write!(f, "{}", self.rows().join("\n"))
     }
 }
 
-fn create_instructions(input: String) -> Vec<Box<dyn Instruction>> {
-    let lines = input.split("\n");
-    let mut instructions: Vec<Box<dyn Instruction>> = vec![];
-    for line in lines {
+/// Parses the instructions, checking that they stay on a screen of `size`.
+pub fn parse(input: &Input, size: Size) -> Result<Vec<Box<dyn Instruction>>, PuzzleError> {
+    error::parse_lines(input.lines(), |line| -> Result<Box<dyn Instruction>, String> {
         if line.contains("rect") {
-            instructions.push(Box::new(Rect::new(line.to_string())));
+            Ok(Box::new(Rect::new(line, size)?))
+        } else if line.contains("row") {
+            Ok(Box::new(RotateRow::new(line, size)?))
+        } else if line.contains("column") {
+            Ok(Box::new(RotateColumn::new(line, size)?))
+        } else {
+            Err(String::from("expected rect, rotate row or rotate column"))
         }
-        if line.contains("row") {
-            instructions.push(Box::new(RotateRow::new(line.to_string())));
-        }
-        if line.contains("column") {
-            instructions.push(Box::new(RotateColumn::new(line.to_string())));
-        }
-    }
-    instructions
+    })
 }
 
-trait Instruction {
+fn numbers<'a>(line: &'a str, separator: &'a str) -> impl Iterator<Item = usize> + 'a {
+    line.split(separator)
+        .flat_map(|word| word.trim_matches(|c| !char::is_numeric(c)).parse::<usize>())
+}
+
+/// A screen operation. `Display` writes it back the way the input spells it.
+pub trait Instruction: Display {
     fn execute(&self, screen: &mut Screen);
 }
 
@@ -126,21 +170,26 @@ struct Rect {
 }
 
 impl Rect {
-    fn new(line: String) -> Rect {
-        let mut numbers = line
-            .split("x")
-            .map(|word| word.trim_matches(|c| !char::is_numeric(c)).parse::<usize>())
-            .filter(|result| result.is_ok())
-            .map(|result| result.unwrap());
-        let a: usize = numbers.next().unwrap();
-        let b: usize = numbers.next().unwrap();
-        Rect { a, b }
+    fn new(line: &str, size: Size) -> Result<Rect, String> {
+        let mut numbers = numbers(line, "x");
+        let a: usize = numbers.next().ok_or("missing rectangle width")?;
+        let b: usize = numbers.next().ok_or("missing rectangle height")?;
+        if a > size.col || b > size.row {
+            return Err(format!("{}x{} rectangle does not fit on the screen", a, b));
+        }
+        Ok(Rect { a, b })
+    }
+}
+
+impl Display for Rect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rect {}x{}", self.a, self.b)
     }
 }
 
 impl Instruction for Rect {
     fn execute(&self, screen: &mut Screen) {
-        screen.rect(&self);
+        screen.rect(self);
     }
 }
 
@@ -150,15 +199,20 @@ struct RotateRow {
 }
 
 impl RotateRow {
-    fn new(line: String) -> RotateRow {
-        let mut numbers = line
-            .split(" ")
-            .map(|word| word.trim_matches(|c| !char::is_numeric(c)).parse::<usize>())
-            .filter(|result| result.is_ok())
-            .map(|result| result.unwrap());
-        let row: usize = numbers.next().unwrap();
-        let amount: usize = numbers.next().unwrap();
-        RotateRow { row, amount }
+    fn new(line: &str, size: Size) -> Result<RotateRow, String> {
+        let mut numbers = numbers(line, " ");
+        let row: usize = numbers.next().ok_or("missing row")?;
+        let amount: usize = numbers.next().ok_or("missing rotation amount")?;
+        if row >= size.row {
+            return Err(format!("row {} is outside the screen", row));
+        }
+        Ok(RotateRow { row, amount })
+    }
+}
+
+impl Display for RotateRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rotate row y={} by {}", self.row, self.amount)
     }
 }
 
@@ -174,52 +228,25 @@ struct RotateColumn {
 }
 
 impl RotateColumn {
-    fn new(line: String) -> RotateColumn {
-        let mut numbers = line
-            .split(" ")
-            .map(|word| word.trim_matches(|c| !char::is_numeric(c)).parse::<usize>())
-            .filter(|result| result.is_ok())
-            .map(|result| result.unwrap());
-        let col: usize = numbers.next().unwrap();
-        let amount: usize = numbers.next().unwrap();
-        RotateColumn { col, amount }
+    fn new(line: &str, size: Size) -> Result<RotateColumn, String> {
+        let mut numbers = numbers(line, " ");
+        let col: usize = numbers.next().ok_or("missing column")?;
+        let amount: usize = numbers.next().ok_or("missing rotation amount")?;
+        if col >= size.col {
+            return Err(format!("column {} is outside the screen", col));
+        }
+        Ok(RotateColumn { col, amount })
     }
 }
 
-impl Instruction for RotateColumn {
-    fn execute(&self, screen: &mut Screen) {
-        screen.rotate_column(self);
+impl Display for RotateColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rotate column x={} by {}", self.col, self.amount)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::any::Any;
-
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    use super::Size;
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str, Option<Box<dyn Any>>)> = vec![
-            (Data::Test(1), "6", Some(Box::new(Size{row: 3, col: 7}))),
-            (Data::Real, "116", None)];
-        for case in cases {
-            let solution = crate::day8::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(8, 1, case.0)).unwrap(), case.2);
-            assert_eq!(solution, case.1);
-        }
-    }
-
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![];
-        for case in cases {
-            let solution = crate::day8::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(8, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+impl Instruction for RotateColumn {
+    fn execute(&self, screen: &mut Screen) {
+        screen.rotate_column(self);
     }
 }
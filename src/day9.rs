@@ -1,57 +1,85 @@
-use std::{any::Any, str::Chars};
+use std::str::Chars;
 
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::{self, PuzzleError},
+    input::Input,
+    params::Params,
+    registry::Entry,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: 9,
+    name: "Explosives in Cyberspace",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        decompress_once(input).len().to_string()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_1(input.trimmed())?.into())
     }
-    fn part_2(&self, input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        decompress_full(input).to_string()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        Ok(part_2(input.trimmed())?.into())
     }
 }
 
-fn decompress_once(input: String) -> String {
+/// The length of the file decompressed with format version 1.
+pub fn part_1(input: &str) -> Result<u64, PuzzleError> {
+    Ok(decompress_once(input)?.chars().count() as u64)
+}
+
+/// The length of the file decompressed with format version 2, where markers
+/// inside decompressed data are expanded too.
+pub fn part_2(input: &str) -> Result<u64, PuzzleError> {
+    decompress_full(input)
+}
+
+/// Expands the markers of `input`, leaving markers in the repeated data alone.
+pub fn decompress_once(input: &str) -> Result<String, PuzzleError> {
     let mut result = String::new();
     let mut chars = input.chars();
     while let Some(c) = chars.next() {
         match c {
             '(' => {
-                let marker = get_marker(&mut chars);
-                result += &marker.decompress_once(&mut chars);
+                let marker = get_marker(&mut chars)?;
+                result += &marker.decompress_once(&mut chars)?;
             }
             c => result.push(c),
         }
     }
-    result
+    Ok(result)
 }
 
-fn decompress_full(input: String) -> usize {
+fn decompress_full(input: &str) -> Result<u64, PuzzleError> {
     let mut len = 0;
     let mut chars = input.chars();
     while let Some(c) = chars.next() {
         match c {
             '(' => {
-                let marker = get_marker(&mut chars);
-                len += &marker.decompress_full(&mut chars);
+                let marker = get_marker(&mut chars)?;
+                len += marker.decompress_full(&mut chars)?;
             }
             _ => len += 1,
         }
     }
-    len
+    Ok(len)
 }
 
-fn get_marker(input: &mut Chars<'_>) -> Marker {
+fn get_marker(input: &mut Chars<'_>) -> Result<Marker, PuzzleError> {
     let mut marker = String::new();
-    while let Some(c) = input.next() {
+    for c in input.by_ref() {
         match c {
-            ')' => return Marker::new(marker),
+            ')' => {
+                return Marker::new(&marker)
+                    .map_err(|err| PuzzleError::new(err).with_text(&format!("({})", marker)))
+            }
             c => marker.push(c),
         };
     }
-    panic!("Marker not finished!");
+    Err(PuzzleError::new("marker is not closed").with_text(&format!("({}", marker)))
 }
 
 #[derive(Debug)]
@@ -61,66 +89,29 @@ struct Marker {
 }
 
 impl Marker {
-    fn new(input: String) -> Marker {
+    fn new(input: &str) -> Result<Marker, String> {
         let mut parts = input.split('x');
-        let length = parts.next().unwrap().parse::<usize>().unwrap();
-        let repeat = parts.next().unwrap().parse::<usize>().unwrap();
-        Marker { length, repeat }
+        let length = error::number(parts.next(), "the length of the marker")?;
+        let repeat = error::number(parts.next(), "the repeat count of the marker")?;
+        Ok(Marker { length, repeat })
     }
 
-    fn decompress_once(&self, chars: &mut Chars<'_>) -> String {
-        let mut part = String::new();
-        for _ in 0..self.length {
-            part.push(chars.next().unwrap());
+    fn take(&self, chars: &mut Chars<'_>) -> Result<String, PuzzleError> {
+        let part = chars.take(self.length).collect::<String>();
+        if part.chars().count() < self.length {
+            return Err(PuzzleError::new(format!(
+                "marker ({}x{}) reaches past the end of the input",
+                self.length, self.repeat
+            )));
         }
-        part.repeat(self.repeat)
+        Ok(part)
     }
 
-    fn decompress_full(&self, chars: &mut Chars<'_>) -> usize {
-        let mut part = String::new();
-        for _ in 0..self.length {
-            part.push(chars.next().unwrap());
-        }
-        self.repeat * decompress_full(part)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
-
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(1), "6"),
-            (Data::Test(2), "7"),
-            (Data::Test(3), "9"),
-            (Data::Test(4), "11"),
-            (Data::Test(5), "6"),
-            (Data::Test(6), "18"),
-            (Data::Real, "107035"),
-        ];
-        for case in cases {
-            let solution = crate::day9::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(9, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    fn decompress_once(&self, chars: &mut Chars<'_>) -> Result<String, PuzzleError> {
+        Ok(self.take(chars)?.repeat(self.repeat))
     }
 
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![
-            (Data::Test(3), "9"),
-            (Data::Test(6), "20"),
-            (Data::Test(7), "241920"),
-            (Data::Test(8), "445"),
-            (Data::Real, "11451628995"),
-        ];
-        for case in cases {
-            let solution = crate::day9::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(9, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
+    fn decompress_full(&self, chars: &mut Chars<'_>) -> Result<u64, PuzzleError> {
+        Ok(self.repeat as u64 * decompress_full(&self.take(chars)?)?)
     }
 }
@@ -0,0 +1,109 @@
+use std::fmt::Display;
+
+/// Why a puzzle could not be solved, pointing at the offending input where known.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PuzzleError {
+    pub day: Option<i32>,
+    pub line: Option<usize>,
+    pub text: Option<String>,
+    pub message: String,
+}
+
+impl PuzzleError {
+    pub fn new(message: impl Into<String>) -> PuzzleError {
+        PuzzleError {
+            day: None,
+            line: None,
+            text: None,
+            message: message.into(),
+        }
+    }
+
+    /// Points the error at a 1-based input line.
+    pub fn at_line(mut self, line: usize, text: &str) -> PuzzleError {
+        self.line = Some(line);
+        self.text = Some(text.to_string());
+        self
+    }
+
+    /// Attaches the offending text, for inputs that are not split into lines.
+    pub fn with_text(mut self, text: &str) -> PuzzleError {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    pub fn in_day(mut self, day: i32) -> PuzzleError {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl From<String> for PuzzleError {
+    fn from(message: String) -> PuzzleError {
+        PuzzleError::new(message)
+    }
+}
+
+impl From<&str> for PuzzleError {
+    fn from(message: &str) -> PuzzleError {
+        PuzzleError::new(message)
+    }
+}
+
+impl Display for PuzzleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = match (self.day, self.line) {
+            (Some(day), Some(line)) => format!("day {}, line {}: ", day, line),
+            (Some(day), None) => format!("day {}: ", day),
+            (None, Some(line)) => format!("line {}: ", line),
+            (None, None) => String::new(),
+        };
+        write!(f, "{}{}", location, self.message)?;
+        match &self.text {
+            Some(text) => write!(f, "\n  | {}", text),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses every one of `lines`, reporting the first failure with its line number and text.
+pub fn parse_lines<'a, T>(
+    lines: impl IntoIterator<Item = &'a str>,
+    mut parse: impl FnMut(&str) -> Result<T, String>,
+) -> Result<Vec<T>, PuzzleError> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|message| PuzzleError::new(message).at_line(i + 1, line)))
+        .collect()
+}
+
+/// Parses `word` as a number, naming `what` was expected when it is missing or malformed.
+pub fn number<T: std::str::FromStr>(word: Option<&str>, what: &str) -> Result<T, String> {
+    match word {
+        Some(word) => word
+            .parse()
+            .map_err(|_| format!("expected {}, got '{}'", what, word)),
+        None => Err(format!("missing {}", what)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_lines, PuzzleError};
+
+    #[test]
+    fn display() {
+        let error = PuzzleError::new("unknown command").at_line(3, "jump a").in_day(12);
+        assert_eq!(error.to_string(), "day 12, line 3: unknown command\n  | jump a");
+        assert_eq!(PuzzleError::new("no path").to_string(), "no path");
+    }
+
+    #[test]
+    fn lines() {
+        let parsed = parse_lines("1\n2\nx\n4".lines(), |line| line.parse::<i32>().map_err(|e| e.to_string()));
+        let error = parsed.unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.text.as_deref(), Some("x"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Sub},
+};
+
+/// The integer types points can be made of.
+pub trait Int: Copy + Ord + Hash + Debug + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! int {
+    ($($int:ty),*) => {
+        $(impl Int for $int {
+            const ZERO: $int = 0;
+            const ONE: $int = 1;
+            fn checked_add(self, other: $int) -> Option<$int> {
+                <$int>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: $int) -> Option<$int> {
+                <$int>::checked_sub(self, other)
+            }
+        })*
+    };
+}
+
+int!(i32, i64, isize, u32, usize);
+
+/// A point of the plane. `x` grows to the east and `y` to the south, like the
+/// columns and rows of a map read from the input.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Int> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    pub fn origin() -> Point<T> {
+        Point::new(T::ZERO, T::ZERO)
+    }
+
+    /// The point `amount` steps towards `direction`, or `None` if it does not fit in `T`.
+    pub fn checked_step_by(self, direction: Direction, amount: T) -> Option<Point<T>> {
+        let (dx, dy) = direction.offset();
+        Some(Point::new(shift(self.x, dx, amount)?, shift(self.y, dy, amount)?))
+    }
+
+    pub fn checked_step(self, direction: Direction) -> Option<Point<T>> {
+        self.checked_step_by(direction, T::ONE)
+    }
+
+    /// The point `amount` steps towards `direction`. Panics if it does not fit in `T`.
+    pub fn step_by(self, direction: Direction, amount: T) -> Point<T> {
+        self.checked_step_by(direction, amount)
+            .unwrap_or_else(|| panic!("stepping {:?} from {:?} leaves the plane", direction, self))
+    }
+
+    pub fn step(self, direction: Direction) -> Point<T> {
+        self.step_by(direction, T::ONE)
+    }
+
+    /// The steps between the points when moving only horizontally and vertically.
+    pub fn manhattan(self, other: Point<T>) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+
+    /// The steps between the points when diagonal moves are allowed too.
+    pub fn chebyshev(self, other: Point<T>) -> T {
+        distance(self.x, other.x).max(distance(self.y, other.y))
+    }
+}
+
+fn shift<T: Int>(value: T, sign: i8, amount: T) -> Option<T> {
+    match sign {
+        1 => value.checked_add(amount),
+        -1 => value.checked_sub(amount),
+        _ => Some(value),
+    }
+}
+
+fn distance<T: Int>(a: T, b: T) -> T {
+    a.max(b) - a.min(b)
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// The directions of horizontal and vertical moves, clockwise from north.
+    pub const CARDINAL: [Direction; 4] =
+        [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The change of `x` and `y` of a step, each -1, 0 or 1.
+    pub fn offset(self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    /// The direction after a quarter turn.
+    pub fn turn(self, turn: Turn) -> Direction {
+        let index = Direction::ALL.iter().position(|direction| *direction == self).unwrap();
+        match turn {
+            Turn::Left => Direction::ALL[(index + 6) % 8],
+            Turn::Right => Direction::ALL[(index + 2) % 8],
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn(Turn::Right).turn(Turn::Right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point, Turn};
+
+    #[test]
+    fn turns() {
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+        assert_eq!(Direction::West.turn(Turn::Right), Direction::North);
+        assert_eq!(Direction::NorthEast.turn(Turn::Right), Direction::SouthEast);
+        assert_eq!(Direction::South.opposite(), Direction::North);
+    }
+
+    #[test]
+    fn steps_and_distances() {
+        let point = Point::new(2, -3).step_by(Direction::North, 4).step(Direction::SouthWest);
+        assert_eq!(point, Point::new(1, -6));
+        assert_eq!(point.manhattan(Point::origin()), 7);
+        assert_eq!(point.chebyshev(Point::origin()), 6);
+        assert_eq!(Point::<usize>::origin().checked_step(Direction::West), None);
+        assert_eq!(Point::new(3_usize, 1).manhattan(Point::new(1, 4)), 5);
+        assert_eq!(Point::new(3, 1).to_string(), "3,1");
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    error::PuzzleError,
+    geometry::{Direction, Point},
+};
+
+/// A cell of a grid: `x` is its column and `y` its row, counted from the top left corner.
+pub type Pos = Point<usize>;
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// The grid mirrored along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Grid<T> {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for col in 0..self.width {
+            cells.extend(self.column(col).cloned());
+        }
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which must all be as wide as the first one.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Grid<T>, String> {
+        let mut grid = Grid {
+            width: 0,
+            height: 0,
+            cells: vec![],
+        };
+        for row in rows {
+            grid.push_row(row)?;
+        }
+        Ok(grid)
+    }
+
+    /// Reads a grid with one row per line, turning every character into a cell with `cell`.
+    /// Errors point at the offending line.
+    pub fn parse<'a>(
+        lines: impl IntoIterator<Item = &'a str>,
+        cell: impl Fn(char) -> Result<T, String>,
+    ) -> Result<Grid<T>, PuzzleError> {
+        let mut grid = Grid::from_rows(vec![])?;
+        for (i, line) in lines.into_iter().enumerate() {
+            line.chars()
+                .map(&cell)
+                .collect::<Result<Vec<T>, String>>()
+                .and_then(|row| grid.push_row(row))
+                .map_err(|err| PuzzleError::new(err).at_line(i + 1, line))?;
+        }
+        Ok(grid)
+    }
+
+    /// Adds a row at the bottom. The first row sets the width of the grid.
+    pub fn push_row(&mut self, row: Vec<T>) -> Result<(), String> {
+        if self.height == 0 {
+            self.width = row.len();
+        } else if row.len() != self.width {
+            return Err(format!("every row must be {} wide, got {}", self.width, row.len()));
+        }
+        self.cells.extend(row);
+        self.height += 1;
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.y < self.height && pos.x < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        match self.contains(pos) {
+            true => self.cells.get(pos.y * self.width + pos.x),
+            false => None,
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        (0..self.height).map(move |row| &self[Pos::new(col, row)])
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Pos::new(i % width, i / width), cell))
+    }
+
+    /// The position of the first cell, row by row, that matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(pos, _)| pos)
+    }
+
+    /// The cells above, right of, below and left of `pos` that are on the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &Direction::CARDINAL)
+    }
+
+    /// The cells around `pos`, diagonals included, that are on the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &Direction::ALL)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        pos: Pos,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        directions
+            .iter()
+            .filter_map(move |direction| pos.checked_step(*direction))
+            .filter(|pos| self.contains(*pos))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The rows drawn with one character per cell.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> Vec<String> {
+        self.rows().map(|row| row.iter().map(&cell).collect()).collect()
+    }
+
+    /// Shifts the cells of `row` right by `amount`, wrapping around.
+    pub fn rotate_row(&mut self, row: usize, amount: usize) {
+        if self.cells.is_empty() {
+            return;
+        }
+        let width = self.width;
+        self.cells[row * width..(row + 1) * width].rotate_right(amount % width);
+    }
+
+    /// Shifts the cells of `col` down by `amount`, wrapping around. Rotates in place by
+    /// reversing the whole column and then both of its parts, so every cell moves twice.
+    pub fn rotate_column(&mut self, col: usize, amount: usize) {
+        if self.cells.is_empty() {
+            return;
+        }
+        let amount = amount % self.height;
+        self.reverse_column(col, 0, self.height);
+        self.reverse_column(col, 0, amount);
+        self.reverse_column(col, amount, self.height);
+    }
+
+    /// Reverses the cells of `col` in the rows from `start` up to `end`.
+    fn reverse_column(&mut self, col: usize, start: usize, end: usize) {
+        let (mut top, mut bottom) = (start, end);
+        while top + 1 < bottom {
+            bottom -= 1;
+            self.cells.swap(top * self.width + col, bottom * self.width + col);
+            top += 1;
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{:?} is outside the {}x{} grid", pos, self.width, self.height);
+        &self.cells[pos.y * self.width + pos.x]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{:?} is outside the {}x{} grid", pos, self.width, self.height);
+        &mut self.cells[pos.y * self.width + pos.x]
+    }
+}
+
+/// Writes every row on its own line, with the cells next to each other.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Pos};
+
+    fn grid() -> Grid<char> {
+        Grid::parse(["abc", "def"], Ok).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(2, 1)], 'f');
+        assert_eq!(grid.get(Pos::new(0, 2)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+        let error = Grid::parse(["ab", "c"], Ok).unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let error = Grid::<char>::parse(["ab", "c#"], |c| match c {
+            '#' => Err(String::from("no walls")),
+            c => Ok(c),
+        });
+        assert_eq!(error.unwrap_err().message, "no walls");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = grid();
+        let corner: Vec<Pos> = grid.neighbours4(Pos::new(0, 0)).collect();
+        assert_eq!(corner, [Pos::new(1, 0), Pos::new(0, 1)]);
+        assert_eq!(grid.neighbours4(Pos::new(1, 1)).count(), 3);
+        assert_eq!(grid.neighbours8(Pos::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn rotate_and_transpose() {
+        let mut grid = grid();
+        grid.rotate_row(0, 4);
+        assert_eq!(grid.to_string(), "cab\ndef");
+        grid.rotate_column(2, 1);
+        assert_eq!(grid.to_string(), "caf\ndeb");
+        assert_eq!(grid.transpose().to_string(), "cd\nae\nfb");
+        assert_eq!(grid.render(|c| c.to_ascii_uppercase()), ["CAF", "DEB"]);
+        grid.rotate_column(0, 5);
+        assert_eq!(grid.to_string(), "daf\nceb");
+        let mut empty = Grid::new(0, 3, 'x');
+        empty.rotate_row(1, 2);
+        empty.rotate_column(0, 2);
+        assert_eq!(Grid::new(3, 0, 'x').transpose().width(), 0);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::grid::Grid;
+
+/// The pixels as a plain PBM image, lit pixels black.
+pub fn pbm(pixels: &Grid<bool>) -> String {
+    let mut image = format!("P1\n{} {}\n", pixels.width(), pixels.height());
+    for row in pixels.rows() {
+        let row: Vec<&str> = row.iter().map(|pixel| if *pixel { "1" } else { "0" }).collect();
+        image += &row.join(" ");
+        image.push('\n');
+    }
+    image
+}
+
+/// The pixels as a grayscale PNG image, lit pixels black and every pixel a `scale` by `scale` square.
+pub fn png(pixels: &Grid<bool>, scale: usize) -> Vec<u8> {
+    let width = pixels.width() * scale;
+    let height = pixels.height() * scale;
+    let mut scanlines = Vec::with_capacity((width + 1) * height);
+    for row in pixels.rows() {
+        // Every scanline starts with its filter type, 0 for none.
+        let mut scanline = vec![0];
+        for pixel in row {
+            let value = if *pixel { 0 } else { 255 };
+            scanline.extend(std::iter::repeat_n(value, scale));
+        }
+        for _ in 0..scale {
+            scanlines.extend(&scanline);
+        }
+    }
+    let mut header = vec![];
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    // 8 bit grayscale, default compression and filtering, no interlacing.
+    header.extend([8, 0, 0, 0, 0]);
+    let mut image = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    chunk(&mut image, b"IHDR", &header);
+    chunk(&mut image, b"IDAT", &zlib(&scanlines));
+    chunk(&mut image, b"IEND", &[]);
+    image
+}
+
+fn chunk(image: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    image.extend((data.len() as u32).to_be_bytes());
+    let start = image.len();
+    image.extend(kind);
+    image.extend(data);
+    let crc = crc32(&image[start..]);
+    image.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        stream.push(u8::from(blocks.peek().is_none()));
+        let length = block.len() as u16;
+        stream.extend(length.to_le_bytes());
+        stream.extend((!length).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adler32, crc32, pbm, png};
+    use crate::grid::Grid;
+
+    #[test]
+    fn pbm_image() {
+        let pixels = Grid::from_rows([vec![true, false], vec![false, true]]).unwrap();
+        assert_eq!(pbm(&pixels), "P1\n2 2\n1 0\n0 1\n");
+    }
+
+    #[test]
+    fn png_image() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        let image = png(&Grid::from_rows([vec![true, false, true]]).unwrap(), 2);
+        assert_eq!(&image[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&image[16..24], [0, 0, 0, 6, 0, 0, 0, 2]);
+        assert!(image.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+}
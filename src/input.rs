@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use crate::error::PuzzleError;
+
+/// Puzzle input as read from a file or stdin, normalized so parsers see the same
+/// text whatever editor wrote it: a leading byte order mark is dropped, `\r\n`
+/// line endings become `\n` and trailing newlines are removed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Input {
+        let text = raw.strip_prefix('\u{feff}').unwrap_or(raw).replace("\r\n", "\n");
+        Input {
+            text: text.trim_end_matches(['\n', '\r']).to_string(),
+        }
+    }
+
+    /// The whole normalized text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The text without surrounding whitespace, for inputs that are a single word or number.
+    pub fn trimmed(&self) -> &str {
+        self.text.trim()
+    }
+
+    /// The lines of the text. Blank lines inside the text are kept, so line
+    /// numbers in errors match the file.
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    /// The characters of every line, for map-like inputs.
+    pub fn grid(&self) -> Vec<Vec<char>> {
+        self.lines().map(|line| line.chars().collect()).collect()
+    }
+
+    /// The whitespace separated numbers of every line, one vector per line.
+    pub fn numbers<T: FromStr>(&self) -> Result<Vec<Vec<T>>, PuzzleError> {
+        self.lines()
+            .enumerate()
+            .map(|(i, line)| {
+                line.split_whitespace()
+                    .map(|word| {
+                        word.parse()
+                            .map_err(|_| PuzzleError::new(format!("expected a number, got '{}'", word)).at_line(i + 1, line))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Input;
+
+    #[test]
+    fn normalization() {
+        let input = Input::new("\u{feff}ab\r\ncd\r\n\r\n");
+        assert_eq!(input.text(), "ab\ncd");
+        assert_eq!(input.lines().collect::<Vec<&str>>(), ["ab", "cd"]);
+        assert_eq!(input.grid(), [['a', 'b'], ['c', 'd']]);
+        assert_eq!(Input::new("  10\n").trimmed(), "10");
+        assert_eq!(Input::new("a\n\nb\n").lines().count(), 3);
+    }
+
+    #[test]
+    fn numbers() {
+        let input = Input::new("1 2  3\n4\n");
+        assert_eq!(input.numbers::<i32>(), Ok(vec![vec![1, 2, 3], vec![4]]));
+        let error = Input::new("1\n2 x").numbers::<i32>().unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.text.as_deref(), Some("2 x"));
+    }
+}
@@ -1,46 +1,103 @@
-use std::process;
+use std::{env, process};
 
-use common::Config;
-
-use crate::common::EnvConfig;
-
-mod common;
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
+use aoc_2016::{
+    answer::Answer,
+    bench,
+    common::{self, Config, EnvConfig, Format},
+    registry, render, runner, scaffold, trace, verify,
+};
 
 fn main() {
-    let config = EnvConfig::new().unwrap_or_else(|err| {
-        println!("Problem parsing arguments: {}", err);
-        process::exit(1);
-    });
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("bench") => {
+            let config = arguments(bench::BenchConfig::new(&args[2..]));
+            let results = bench::run(&config);
+            bench::print_table(&results);
+            or_exit(bench::write_report(&config, &results));
+            if results.iter().any(|result| result.is_failure()) {
+                process::exit(1);
+            }
+        }
+        Some("trace") => {
+            let config = arguments(trace::TraceConfig::new(&args[2..]));
+            or_exit(trace::run(&config));
+        }
+        Some("decompile") => {
+            let config = arguments(trace::DecompileConfig::new(&args[2..]));
+            or_exit(trace::decompile(&config));
+        }
+        Some("render") => {
+            let config = arguments(render::RenderConfig::new(&args[2..]));
+            or_exit(render::render(&config));
+        }
+        Some("new-day") => {
+            let config = arguments(scaffold::NewDayConfig::new(&args[2..]));
+            or_exit(scaffold::new_day(&config));
+        }
+        Some("list") => registry::print_list(),
+        Some("verify") => {
+            let config = arguments(verify::VerifyConfig::new(&args[2..]));
+            let verdicts = or_exit(verify::run(&config));
+            verify::print_table(&verdicts, config.record);
+            if verdicts.iter().any(|verdict| verdict.is_failure()) {
+                process::exit(1);
+            }
+        }
+        Some("all") => {
+            let config = arguments(runner::AllConfig::new(&args[2..]));
+            let results = runner::run_all(config.threads);
+            match config.format {
+                Format::Text => runner::print_table(&results),
+                Format::Json => runner::print_json(&results),
+            }
+            if results.iter().any(|result| result.is_failure()) {
+                process::exit(1);
+            }
+        }
+        _ => solve(),
+    }
+}
+
+/// Solves the day and part given on the command line.
+fn solve() {
+    let config = arguments(EnvConfig::new());
+    if config.get_format() == Format::Json {
+        let result = runner::run_config(config);
+        println!("{}", result.to_json());
+        if result.is_failure() {
+            process::exit(1);
+        }
+        return;
+    }
     let input = common::read_input(&config).unwrap_or_else(|err| {
         println!("Cannot read input file: {}", err);
         process::exit(1);
     });
-    let msg = format!("The solution for day {} part {} is: ", config.get_day(), config.get_part());
-    let solution = common::solve(input, config);
-    println!("{}{}!", msg, solution);
+    let (day, part) = (config.get_day(), config.get_part());
+    let solution = common::solve(&input, config).unwrap_or_else(|err| {
+        println!("Error in {}", err);
+        process::exit(1);
+    });
+    match solution {
+        Answer::Grid(_) => println!("The solution for day {} part {} is:\n{}", day, part, solution),
+        Answer::NoAnswer => println!("There is no answer for day {} part {}", day, part),
+        _ => println!("The solution for day {} part {} is: {}!", day, part, solution),
+    }
+}
+
+/// The parsed arguments of a subcommand, or exits saying what is wrong with them.
+fn arguments<T>(config: Result<T, String>) -> T {
+    config.unwrap_or_else(|err| {
+        println!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    })
+}
+
+/// The result of a subcommand, or exits printing its error.
+fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        println!("{}", err);
+        process::exit(1);
+    })
 }
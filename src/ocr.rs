@@ -0,0 +1,100 @@
+use crate::grid::Grid;
+
+/// A fixed width font of capital letters, as lit on the screens of the puzzles.
+/// Other sizes are added by describing them with another `Font`.
+pub struct Font {
+    /// Columns per letter, including the dark columns that separate it from the next one.
+    pub width: usize,
+    pub height: usize,
+    /// Every known letter with its rows, `#` for a lit and `.` for a dark pixel.
+    pub glyphs: &'static [(char, &'static [&'static str])],
+}
+
+/// The 5 wide, 6 tall font of the 2016 screens.
+pub const FONT_5X6: Font = Font {
+    width: 5,
+    height: 6,
+    glyphs: &[
+        ('A', &[".##..", "#..#.", "#..#.", "####.", "#..#.", "#..#."]),
+        ('B', &["###..", "#..#.", "###..", "#..#.", "#..#.", "###.."]),
+        ('C', &[".##..", "#..#.", "#....", "#....", "#..#.", ".##.."]),
+        ('E', &["####.", "#....", "###..", "#....", "#....", "####."]),
+        ('F', &["####.", "#....", "###..", "#....", "#....", "#...."]),
+        ('G', &[".##..", "#..#.", "#....", "#.##.", "#..#.", ".###."]),
+        ('H', &["#..#.", "#..#.", "####.", "#..#.", "#..#.", "#..#."]),
+        ('I', &[".###.", "..#..", "..#..", "..#..", "..#..", ".###."]),
+        ('J', &["..##.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+        ('K', &["#..#.", "#.#..", "##...", "#.#..", "#.#..", "#..#."]),
+        ('L', &["#....", "#....", "#....", "#....", "#....", "####."]),
+        ('O', &[".##..", "#..#.", "#..#.", "#..#.", "#..#.", ".##.."]),
+        ('P', &["###..", "#..#.", "#..#.", "###..", "#....", "#...."]),
+        ('R', &["###..", "#..#.", "#..#.", "###..", "#.#..", "#..#."]),
+        ('S', &[".###.", "#....", "#....", ".##..", "...#.", "###.."]),
+        ('U', &["#..#.", "#..#.", "#..#.", "#..#.", "#..#.", ".##.."]),
+        ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+        ('Z', &["####.", "...#.", "..#..", ".#...", "#....", "####."]),
+    ],
+};
+
+impl Font {
+    /// Reads the letters off the pixels, one letter per `width` columns.
+    /// A letter the font does not know is reported with its bitmap.
+    pub fn read(&self, pixels: &Grid<bool>) -> Result<String, String> {
+        if pixels.height() != self.height {
+            return Err(format!(
+                "the screen is {} pixels tall, the font {}",
+                pixels.height(),
+                self.height
+            ));
+        }
+        let mut text = String::new();
+        for start in (0..pixels.width()).step_by(self.width) {
+            let rows: Vec<String> = pixels
+                .rows()
+                .map(|row| {
+                    (start..start + self.width)
+                        .map(|col| if row.get(col) == Some(&true) { '#' } else { '.' })
+                        .collect()
+                })
+                .collect();
+            let glyph = self
+                .glyphs
+                .iter()
+                .find(|(_, glyph)| glyph.len() == rows.len() && glyph.iter().zip(&rows).all(|(a, b)| a == b));
+            match glyph {
+                Some((letter, _)) => text.push(*letter),
+                None => return Err(format!("unknown glyph at column {}:\n{}", start, rows.join("\n"))),
+            }
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FONT_5X6;
+    use crate::grid::Grid;
+
+    fn pixels(rows: &[&str]) -> Grid<bool> {
+        Grid::parse(rows.iter().copied(), |c| Ok(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn read() {
+        let screen = pixels(&[
+            "#..#.###..#...#",
+            "#..#.#..#.#...#",
+            "####.###...#.#.",
+            "#..#.#..#...#..",
+            "#..#.#..#...#..",
+            "#..#.###....#..",
+        ]);
+        assert_eq!(FONT_5X6.read(&screen), Ok(String::from("HBY")));
+        let unknown = pixels(&["#....", ".#...", "..#..", "...#.", "....#", "....."]);
+        assert_eq!(
+            FONT_5X6.read(&unknown),
+            Err(String::from("unknown glyph at column 0:\n#....\n.#...\n..#..\n...#.\n....#\n....."))
+        );
+        assert!(FONT_5X6.read(&pixels(&["#"])).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use std::{collections::HashMap, fmt::Display};
+
+/// The type of a puzzle parameter and the values it accepts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Int { min: i64, max: i64 },
+    Text,
+    Choice(&'static [&'static str]),
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Int { min, max } => write!(f, "integer in {}..={}", min, max),
+            Kind::Text => write!(f, "text"),
+            Kind::Choice(choices) => write!(f, "one of {}", choices.join(", ")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Text(String),
+}
+
+/// A named parameter a puzzle accepts, with a default value for each part.
+pub struct Param {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub defaults: [&'static str; 2],
+    pub description: &'static str,
+}
+
+impl Param {
+    pub fn new(
+        name: &'static str,
+        kind: Kind,
+        defaults: [&'static str; 2],
+        description: &'static str,
+    ) -> Param {
+        Param {
+            name,
+            kind,
+            defaults,
+            description,
+        }
+    }
+
+    fn parse(&self, value: &str) -> Result<Value, String> {
+        match self.kind {
+            Kind::Int { min, max } => match value.parse::<i64>() {
+                Ok(number) if (min..=max).contains(&number) => Ok(Value::Int(number)),
+                _ => Err(format!(
+                    "Parameter '{}' expects an {}, got '{}'",
+                    self.name, self.kind, value
+                )),
+            },
+            Kind::Text => Ok(Value::Text(value.to_string())),
+            Kind::Choice(choices) if choices.contains(&value) => Ok(Value::Text(value.to_string())),
+            Kind::Choice(_) => Err(format!(
+                "Parameter '{}' expects {}, got '{}'",
+                self.name, self.kind, value
+            )),
+        }
+    }
+}
+
+/// Parameter values of one puzzle part, validated against the puzzle's [`Param`]s.
+#[derive(Default, Debug)]
+pub struct Params {
+    values: HashMap<&'static str, Value>,
+}
+
+impl Params {
+    /// Fills in the defaults of `part` and applies `overrides` given as `(name, value)` pairs.
+    pub fn resolve(specs: &[Param], part: i32, overrides: &[(String, String)]) -> Result<Params, String> {
+        if !(1..=2).contains(&part) {
+            return Err(format!("Invalid part {}", part));
+        }
+        let mut values = HashMap::new();
+        for spec in specs {
+            values.insert(spec.name, spec.parse(spec.defaults[part as usize - 1])?);
+        }
+        for (i, (name, value)) in overrides.iter().enumerate() {
+            if overrides[..i].iter().any(|(other, _)| other == name) {
+                return Err(format!("Parameter '{}' is given more than once", name));
+            }
+            let spec = specs.iter().find(|spec| spec.name == name).ok_or_else(|| {
+                if specs.is_empty() {
+                    return format!("Unknown parameter '{}', this puzzle takes no parameters", name);
+                }
+                let expected = specs
+                    .iter()
+                    .map(|spec| {
+                        format!(
+                            "\n  {}: {} ({}, default {})",
+                            spec.name,
+                            spec.description,
+                            spec.kind,
+                            spec.defaults[part as usize - 1]
+                        )
+                    })
+                    .collect::<String>();
+                format!("Unknown parameter '{}', expected one of:{}", name, expected)
+            })?;
+            values.insert(spec.name, spec.parse(value)?);
+        }
+        Ok(Params { values })
+    }
+
+    /// Returns an integer parameter. Panics if the puzzle did not declare it as one.
+    pub fn int(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(Value::Int(value)) => *value,
+            _ => panic!("Integer parameter '{}' is not declared", name),
+        }
+    }
+
+    /// Returns a text parameter. Panics if the puzzle did not declare it as one.
+    pub fn text(&self, name: &str) -> &str {
+        match self.values.get(name) {
+            Some(Value::Text(value)) => value,
+            _ => panic!("Text parameter '{}' is not declared", name),
+        }
+    }
+}
+
+/// Splits a `key=value` command line argument.
+pub fn parse_assignment(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("Parameter must be given as key=value, got '{}'", arg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_assignment, Kind, Param, Params};
+
+    fn specs() -> Vec<Param> {
+        vec![
+            Param::new("rows", Kind::Int { min: 1, max: 100 }, ["40", "80"], "rows"),
+            Param::new("name", Kind::Text, ["abc", "abc"], "name"),
+            Param::new("mode", Kind::Choice(&["fast", "slow"]), ["fast", "slow"], "mode"),
+        ]
+    }
+
+    fn overrides(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn defaults() {
+        let params = Params::resolve(&specs(), 2, &[]).unwrap();
+        assert_eq!(params.int("rows"), 80);
+        assert_eq!(params.text("name"), "abc");
+        assert_eq!(params.text("mode"), "slow");
+    }
+
+    #[test]
+    fn overrides_are_validated() {
+        let params = Params::resolve(&specs(), 1, &overrides(&[("rows", "3")])).unwrap();
+        assert_eq!(params.int("rows"), 3);
+        assert!(Params::resolve(&specs(), 1, &overrides(&[("rows", "x")])).is_err());
+        assert!(Params::resolve(&specs(), 1, &overrides(&[("rows", "0")])).is_err());
+        assert!(Params::resolve(&specs(), 1, &overrides(&[("cols", "3")])).is_err());
+        assert!(Params::resolve(&specs(), 1, &overrides(&[("rows", "3"), ("rows", "4")])).is_err());
+        assert!(Params::resolve(&specs(), 1, &overrides(&[("mode", "slow")])).is_ok());
+        assert!(Params::resolve(&specs(), 1, &overrides(&[("mode", "quick")])).is_err());
+        assert!(Params::resolve(&specs(), 0, &[]).is_err());
+        assert!(Params::resolve(&specs(), 3, &[]).is_err());
+    }
+
+    #[test]
+    fn assignment() {
+        assert_eq!(
+            parse_assignment("rows=3"),
+            Ok((String::from("rows"), String::from("3")))
+        );
+        assert!(parse_assignment("rows").is_err());
+        assert!(parse_assignment("=3").is_err());
+    }
+}
@@ -1,40 +1,40 @@
-use std::any::Any;
-
-use crate::common;
+use crate::{
+    answer::Answer,
+    common,
+    error::PuzzleError,
+    input::Input,
+    params::Params,
+    registry::Entry,
+    timing,
+};
 
 pub struct Puzzle {}
 
+pub const ENTRY: Entry = Entry {
+    day: __,
+    name: "Day __",
+    puzzle: &Puzzle {},
+};
+
 impl common::Puzzle for Puzzle {
-    fn part_1(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        String::new()
+    fn part_1(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let lines = timing::parse(|| parse(input))?;
+        Ok(part_1(&lines).into())
     }
-    fn part_2(&self, _input: String, _extra_param: Option<Box<dyn Any>>) -> String {
-        String::new()
+    fn part_2(&self, input: &Input, _params: &Params) -> Result<Answer, PuzzleError> {
+        let lines = timing::parse(|| parse(input))?;
+        Ok(part_2(&lines).into())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::common::common_test::FakeConfig;
-    use crate::common::{read_input, Data, Puzzle};
+pub fn part_1(_lines: &[String]) -> usize {
+    0
+}
 
-    #[test]
-    fn part_1() {
-        let cases: Vec<(Data, &str)> = vec![];
-        for case in cases {
-            let solution = crate::day__::Puzzle {}
-                .part_1(read_input(&FakeConfig::new(__, 1, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+pub fn part_2(_lines: &[String]) -> usize {
+    0
+}
 
-    #[test]
-    fn part_2() {
-        let cases: Vec<(Data, &str)> = vec![];
-        for case in cases {
-            let solution = crate::day__::Puzzle {}
-                .part_2(read_input(&FakeConfig::new(__, 2, case.0)).unwrap(), None);
-            assert_eq!(solution, case.1);
-        }
-    }
+pub fn parse(input: &Input) -> Result<Vec<String>, PuzzleError> {
+    Ok(input.lines().map(String::from).collect())
 }
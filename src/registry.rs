@@ -0,0 +1,80 @@
+use crate::common::Puzzle;
+
+/// A puzzle as registered by its day module.
+pub struct Entry {
+    pub day: i32,
+    pub name: &'static str,
+    pub puzzle: &'static dyn Puzzle,
+}
+
+/// Every solved day, in order. Solving, run-all, benchmarks and the listing all go through this table.
+pub const PUZZLES: &[Entry] = &[
+    crate::day1::ENTRY,
+    crate::day2::ENTRY,
+    crate::day3::ENTRY,
+    crate::day4::ENTRY,
+    crate::day5::ENTRY,
+    crate::day6::ENTRY,
+    crate::day7::ENTRY,
+    crate::day8::ENTRY,
+    crate::day9::ENTRY,
+    crate::day10::ENTRY,
+    crate::day11::ENTRY,
+    crate::day12::ENTRY,
+    crate::day13::ENTRY,
+    crate::day14::ENTRY,
+    crate::day15::ENTRY,
+    crate::day16::ENTRY,
+    crate::day17::ENTRY,
+    crate::day18::ENTRY,
+    crate::day19::ENTRY,
+    crate::day20::ENTRY,
+    crate::day21::ENTRY,
+    crate::day22::ENTRY,
+    crate::day23::ENTRY,
+    crate::day24::ENTRY,
+    crate::day25::ENTRY,
+];
+
+pub fn find(day: i32) -> Option<&'static Entry> {
+    PUZZLES.iter().find(|entry| entry.day == day)
+}
+
+pub fn days() -> impl Iterator<Item = i32> {
+    PUZZLES.iter().map(|entry| entry.day)
+}
+
+/// Prints every registered puzzle with the parameters it accepts.
+pub fn print_list() {
+    for entry in PUZZLES {
+        println!("Day {:>2}: {}", entry.day, entry.name);
+        for param in entry.puzzle.params() {
+            println!(
+                "        {}: {} ({}, default {} / {})",
+                param.name, param.description, param.kind, param.defaults[0], param.defaults[1]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{days, PUZZLES};
+    use crate::params::Params;
+
+    #[test]
+    fn days_are_unique_and_ordered() {
+        let days = days().collect::<Vec<i32>>();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(days.first(), Some(&1));
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        for entry in PUZZLES {
+            for part in 1..=2 {
+                assert!(Params::resolve(&entry.puzzle.params(), part, &[]).is_ok(), "day {}", entry.day);
+            }
+        }
+    }
+}
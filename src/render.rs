@@ -0,0 +1,131 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    common::{self, Config, Data},
+    day8::{self, Screen, Size},
+    params::{self, Params},
+};
+
+pub struct RenderConfig {
+    data_type: Data,
+    input: Option<String>,
+    params: Vec<(String, String)>,
+    out: PathBuf,
+    png: bool,
+    scale: usize,
+    frames: bool,
+}
+
+impl RenderConfig {
+    /// Parses `(<data> | --input <path>) [--param key=value] [--out <dir>] [--png] [--scale n] [--frames]`.
+    pub fn new(args: &[String]) -> Result<RenderConfig, String> {
+        let (data_type, flags) = match args.first() {
+            None => return Err(String::from("render expects <data> or --input <path>")),
+            Some(arg) if arg.starts_with("--") => (Data::Real, args),
+            Some(arg) => (Data::parse(arg)?, &args[1..]),
+        };
+        let mut config = RenderConfig {
+            data_type,
+            input: None,
+            params: vec![],
+            out: PathBuf::from("screen"),
+            png: false,
+            scale: 8,
+            frames: false,
+        };
+        let mut rest = flags.iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--input" => config.input = Some(rest.next().ok_or("Missing value for --input")?.clone()),
+                "--param" => {
+                    let assignment = rest.next().ok_or("Missing key=value after --param")?;
+                    config.params.push(params::parse_assignment(assignment)?);
+                }
+                "--out" => config.out = PathBuf::from(rest.next().ok_or("Missing value for --out")?),
+                "--png" => config.png = true,
+                "--scale" => {
+                    let value = rest.next().ok_or("Missing value for --scale")?;
+                    config.scale = match value.parse::<usize>() {
+                        Ok(scale) if scale > 0 => scale,
+                        _ => return Err(format!("--scale must be a positive integer, got {}", value)),
+                    };
+                }
+                "--frames" => config.frames = true,
+                other => return Err(format!("Unexpected argument: {}", other)),
+            }
+        }
+        Ok(config)
+    }
+
+    fn write(&self, name: &str, screen: &Screen) -> Result<PathBuf, String> {
+        let path = match self.png {
+            true => self.out.join(format!("{}.png", name)),
+            false => self.out.join(format!("{}.pbm", name)),
+        };
+        let image = match self.png {
+            true => screen.to_png(self.scale),
+            false => screen.to_pbm().into_bytes(),
+        };
+        fs::write(&path, image).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+impl Config for RenderConfig {
+    fn get_day(&self) -> i32 {
+        8
+    }
+
+    fn get_part(&self) -> i32 {
+        2
+    }
+
+    fn get_data_type(&self) -> Data {
+        self.data_type
+    }
+
+    fn get_input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+}
+
+/// Draws the day 8 screen after the instructions, or with `--frames` after every
+/// one of them, into image files and lists what was written.
+pub fn render(config: &RenderConfig) -> Result<(), String> {
+    let input = common::read_input(config).map_err(|err| format!("Cannot read input file: {}", err))?;
+    let params = Params::resolve(&common::Puzzle::params(&day8::Puzzle {}), 2, &config.params)?;
+    let size = Size::from_params(&params);
+    let instructions = day8::parse(&input, size).map_err(|err| err.in_day(8).to_string())?;
+    fs::create_dir_all(&config.out).map_err(|e| format!("Cannot create {}: {}", config.out.display(), e))?;
+    if config.frames {
+        let frames = day8::frames(size, &instructions);
+        let width = (frames.len() - 1).to_string().len();
+        for (i, frame) in frames.iter().enumerate() {
+            let path = config.write(&format!("frame_{:0width$}", i, width = width), frame)?;
+            match i {
+                0 => println!("{}: blank screen", path.display()),
+                i => println!("{}: {}", path.display(), instructions[i - 1]),
+            }
+        }
+    } else {
+        let path = config.write("screen", &day8::part_2(size, &instructions))?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenderConfig;
+
+    #[test]
+    fn config() {
+        let args = ["1", "--param", "rows=3", "--png", "--scale", "2", "--frames"].map(String::from);
+        let config = RenderConfig::new(&args).unwrap();
+        assert_eq!(config.params, vec![(String::from("rows"), String::from("3"))]);
+        assert!(config.png && config.frames);
+        assert_eq!(config.scale, 2);
+        assert!(RenderConfig::new(&["real", "--scale", "0"].map(String::from)).is_err());
+        assert!(RenderConfig::new(&[]).is_err());
+    }
+}
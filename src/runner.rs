@@ -0,0 +1,248 @@
+use std::{
+    io,
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answer::Answer,
+    common::{self, Config, Data, EnvConfig, Format},
+    input::Input,
+    json, registry,
+};
+
+pub const PARTS: std::ops::RangeInclusive<i32> = 1..=2;
+
+pub struct JobConfig {
+    pub day: i32,
+    pub part: i32,
+}
+
+impl Config for JobConfig {
+    fn get_day(&self) -> i32 {
+        self.day
+    }
+
+    fn get_part(&self) -> i32 {
+        self.part
+    }
+
+    fn get_data_type(&self) -> Data {
+        Data::Real
+    }
+}
+
+pub struct AllConfig {
+    pub format: Format,
+    pub threads: usize,
+}
+
+impl AllConfig {
+    /// Parses `[--format text|json] [--threads <n>]`. Threads default to the available cores.
+    pub fn new(args: &[String]) -> Result<AllConfig, String> {
+        let mut format = Format::Text;
+        let mut threads = thread::available_parallelism().map_or(1, |cores| cores.get());
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => format = Format::new(args.next().ok_or("Missing value for --format")?)?,
+                "--threads" => {
+                    let value = args.next().ok_or("Missing value for --threads")?;
+                    threads = match value.parse::<usize>() {
+                        Ok(threads) if threads > 0 => threads,
+                        _ => return Err(format!("--threads must be a positive integer, got {}", value)),
+                    };
+                }
+                other => return Err(format!("Unexpected argument: {}", other)),
+            }
+        }
+        Ok(AllConfig { format, threads })
+    }
+}
+
+pub struct RunResult {
+    pub day: i32,
+    pub part: i32,
+    /// The data set that was solved, see [`EnvConfig::get_data_name`].
+    pub data: String,
+    pub outcome: Result<Answer, String>,
+    /// Time spent solving, without reading the input.
+    pub elapsed: Duration,
+}
+
+impl RunResult {
+    pub fn is_failure(&self) -> bool {
+        self.outcome.is_err()
+    }
+
+    pub fn to_json(&self) -> String {
+        let (answer, error) = match &self.outcome {
+            Ok(answer) => (answer.to_json(), String::from("null")),
+            Err(err) => (String::from("null"), json::string(err)),
+        };
+        format!(
+            "{{\"day\": {}, \"part\": {}, \"data\": {}, \"answer\": {}, \"elapsed_ms\": {:.3}, \"error\": {}}}",
+            self.day,
+            self.part,
+            json::string(&self.data),
+            answer,
+            self.elapsed.as_secs_f64() * 1000.0,
+            error
+        )
+    }
+}
+
+/// Solves both parts of every registered day on the real data, catching solver panics.
+/// The parts are shared out to `threads` workers; the results come back in day and part order.
+pub fn run_all(threads: usize) -> Vec<RunResult> {
+    let jobs: Vec<(i32, i32)> = registry::days()
+        .flat_map(|day| PARTS.map(move |part| (day, part)))
+        .collect();
+    let next = AtomicUsize::new(0);
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut results: Vec<RunResult> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, jobs.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    while let Some(&(day, part)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        done.push(run(day, part));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("solver panics are caught"))
+            .collect()
+    });
+    panic::set_hook(default_hook);
+    results.sort_by_key(|result| (result.day, result.part));
+    results
+}
+
+fn run(day: i32, part: i32) -> RunResult {
+    let config = JobConfig { day, part };
+    let (outcome, elapsed) = timed(common::read_input(&config), |input| solve(day, part, input, &[]));
+    RunResult {
+        day,
+        part,
+        data: Data::Real.name(),
+        outcome,
+        elapsed,
+    }
+}
+
+/// Solves the single part the command line asks for.
+pub fn run_config(config: EnvConfig) -> RunResult {
+    let (day, part, data) = (config.get_day(), config.get_part(), config.get_data_name());
+    let input = common::read_input(&config);
+    let (outcome, elapsed) = timed(input, |input| common::solve(input, config).map_err(|err| err.to_string()));
+    RunResult {
+        day,
+        part,
+        data,
+        outcome,
+        elapsed,
+    }
+}
+
+/// Solves `input` if it could be read, timing only the solving.
+fn timed(
+    input: Result<Input, io::Error>,
+    solve: impl FnOnce(&Input) -> Result<Answer, String>,
+) -> (Result<Answer, String>, Duration) {
+    match input {
+        Ok(input) => {
+            let start = Instant::now();
+            let outcome = solve(&input);
+            (outcome, start.elapsed())
+        }
+        Err(err) => (Err(format!("cannot read input file: {}", err)), Duration::ZERO),
+    }
+}
+
+/// Solves one part, turning both errors and panics into an error message.
+pub fn solve(day: i32, part: i32, input: &Input, overrides: &[(String, String)]) -> Result<Answer, String> {
+    catch_panic(|| common::solve_part(day, part, input, overrides))
+        .and_then(|solution| solution.map_err(|err| err.to_string()))
+}
+
+/// Runs `f`, turning a panic into an error carrying the panic message.
+/// Callers are expected to silence the panic hook while this is used.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            String::from("unknown panic")
+        };
+        format!("panicked: {}", message)
+    })
+}
+
+pub fn print_table(results: &[RunResult]) {
+    println!("{:>3} | {:>4} | Answer", "Day", "Part");
+    println!("----+------+-------");
+    for result in results {
+        let answer = match &result.outcome {
+            Ok(answer) => answer.to_string(),
+            Err(err) => format!("FAILED ({})", err),
+        };
+        // Grids continue on the following lines, under the answer column.
+        let mut lines = answer.lines();
+        println!("{:>3} | {:>4} | {}", result.day, result.part, lines.next().unwrap_or_default());
+        for line in lines {
+            println!("{:>3} | {:>4} | {}", "", "", line);
+        }
+    }
+    let failed = results.iter().filter(|result| result.is_failure()).count();
+    println!("{} solved, {} failed", results.len() - failed, failed);
+}
+
+/// Prints one JSON object per line.
+pub fn print_json(results: &[RunResult]) {
+    for result in results {
+        println!("{}", result.to_json());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{AllConfig, RunResult};
+    use crate::{answer::Answer, common::Format};
+
+    #[test]
+    fn json() {
+        let mut result = RunResult {
+            day: 8,
+            part: 2,
+            data: String::from("real"),
+            outcome: Ok(Answer::Grid(vec![String::from("#.")])),
+            elapsed: Duration::from_micros(1500),
+        };
+        assert_eq!(
+            result.to_json(),
+            "{\"day\": 8, \"part\": 2, \"data\": \"real\", \"answer\": [\"#.\"], \"elapsed_ms\": 1.500, \"error\": null}"
+        );
+        result.outcome = Err(String::from("day 8: \"bad\""));
+        assert!(result.to_json().ends_with("\"answer\": null, \"elapsed_ms\": 1.500, \"error\": \"day 8: \\\"bad\\\"\"}"));
+    }
+
+    #[test]
+    fn config() {
+        let config = AllConfig::new(&["--format", "json", "--threads", "3"].map(String::from)).unwrap();
+        assert_eq!(config.format, Format::Json);
+        assert_eq!(config.threads, 3);
+        assert!(AllConfig::new(&["--format", "xml"].map(String::from)).is_err());
+        assert!(AllConfig::new(&["--threads", "0"].map(String::from)).is_err());
+    }
+}
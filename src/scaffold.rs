@@ -0,0 +1,162 @@
+use std::{fs, path::Path};
+
+/// The module of a new day, with `__` standing for the day number.
+const TEMPLATE: &str = include_str!("raw.rs");
+
+pub struct NewDayConfig {
+    /// `None` for the day after the last registered one.
+    day: Option<i32>,
+    name: Option<String>,
+}
+
+impl NewDayConfig {
+    /// Parses `[<day>] [--name <title>]`. The day defaults to the one after the last registered day.
+    pub fn new(args: &[String]) -> Result<NewDayConfig, String> {
+        let mut day = None;
+        let mut name = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--name" => name = Some(args.next().ok_or("Missing value for --name")?.clone()),
+                value => match value.parse::<i32>() {
+                    Ok(number) if number > 0 && day.is_none() => day = Some(number),
+                    _ => return Err(format!("Unexpected argument: {}", value)),
+                },
+            }
+        }
+        Ok(NewDayConfig { day, name })
+    }
+}
+
+/// Creates `src/dayN.rs` from the template and `inputs/dayN/` with empty data
+/// files and an empty test case manifest, and registers the module, all in the
+/// source tree the binary was built from. Nothing is written if the day exists
+/// already, and everything is undone if a step fails.
+pub fn new_day(config: &NewDayConfig) -> Result<(), String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_path = root.join("src/lib.rs");
+    let table_path = root.join("src/registry.rs");
+    let lib = read(&lib_path)?;
+    let table = read(&table_path)?;
+    // The sources, not the compiled table, know about days added since the last build.
+    let days = registered_days(&table);
+    let day = config.day.unwrap_or_else(|| days.iter().max().unwrap_or(&0) + 1);
+    if days.contains(&day) {
+        return Err(format!("Day {} is already registered", day));
+    }
+    let module = root.join(format!("src/day{}.rs", day));
+    let inputs = root.join(format!("inputs/day{}", day));
+    for path in [&module, &inputs] {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()));
+        }
+    }
+    let new_lib = insert_line(&lib, day, &days, |day| format!("pub mod day{};", day))?;
+    let new_table = insert_line(&table, day, &days, |day| format!("    crate::day{}::ENTRY,", day))?;
+    let name = config.name.clone().unwrap_or_else(|| format!("Day {}", day));
+    let created = write(&lib_path, &new_lib)
+        .and_then(|_| write(&table_path, &new_table))
+        .and_then(|_| create_files(day, &name, &module, &inputs));
+    if let Err(err) = created {
+        let _ = fs::write(&lib_path, &lib);
+        let _ = fs::write(&table_path, &table);
+        let _ = fs::remove_file(&module);
+        let _ = fs::remove_dir_all(&inputs);
+        return Err(err);
+    }
+    println!("Created {} and {}/ for day {}: {}", module.display(), inputs.display(), day, name);
+    Ok(())
+}
+
+fn create_files(day: i32, name: &str, module: &Path, inputs: &Path) -> Result<(), String> {
+    write(module, &render(day, name))?;
+    fs::create_dir_all(inputs).map_err(|err| format!("Cannot create {}: {}", inputs.display(), err))?;
+    for file in ["data.txt", "data1.txt"] {
+        write(&inputs.join(file), "")?;
+    }
+    write(&inputs.join("cases.toml"), &manifest(day))
+}
+
+/// The days listed in the source of the registry table.
+fn registered_days(table: &str) -> Vec<i32> {
+    table
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("crate::day")?.strip_suffix("::ENTRY,")?.parse().ok())
+        .collect()
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("Cannot read {}: {}", path.display(), err))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("Cannot write {}: {}", path.display(), err))
+}
+
+fn render(day: i32, name: &str) -> String {
+    TEMPLATE
+        .replace("\"Day __\"", &format!("{:?}", name))
+        .replace("__", &day.to_string())
+}
+
+fn manifest(day: i32) -> String {
+    format!(
+        "# Expected answers for day {}. Files are relative to this directory, params override the defaults.\n\
+         \n\
+         # [[case]]\n\
+         # file = \"data1.txt\"\n\
+         # part = 1\n\
+         # answer = \"\"\n",
+        day
+    )
+}
+
+/// Inserts the line of `day` next to the lines of the existing `days`, keeping them sorted.
+fn insert_line(text: &str, day: i32, days: &[i32], line: impl Fn(i32) -> String) -> Result<String, String> {
+    let mut lines = text.lines().collect::<Vec<&str>>();
+    let position = |other: i32| lines.iter().position(|existing| *existing == line(other));
+    let before = days.iter().filter(|other| **other < day).max().and_then(|other| position(*other));
+    let after = days.iter().filter(|other| **other > day).min().and_then(|other| position(*other));
+    let index = match (before, after) {
+        (Some(index), _) => index + 1,
+        (None, Some(index)) => index,
+        (None, None) => return Err(format!("Cannot find where to add '{}'", line(day))),
+    };
+    let new_line = line(day);
+    lines.insert(index, &new_line);
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{insert_line, registered_days, render};
+
+    #[test]
+    fn lines_stay_sorted() {
+        let text = "pub mod common;\npub mod day1;\npub mod day3;\npub mod error;\n";
+        let line = |day| format!("pub mod day{};", day);
+        assert_eq!(
+            insert_line(text, 2, &[1, 3], line).unwrap(),
+            "pub mod common;\npub mod day1;\npub mod day2;\npub mod day3;\npub mod error;\n"
+        );
+        assert_eq!(
+            insert_line(text, 4, &[1, 3], line).unwrap(),
+            "pub mod common;\npub mod day1;\npub mod day3;\npub mod day4;\npub mod error;\n"
+        );
+        assert!(insert_line("pub mod common;\n", 4, &[1, 3], line).is_err());
+    }
+
+    #[test]
+    fn days_from_source() {
+        let table = "pub const PUZZLES: &[Entry] = &[\n    crate::day1::ENTRY,\n    crate::day12::ENTRY,\n];";
+        assert_eq!(registered_days(table), [1, 12]);
+    }
+
+    #[test]
+    fn template() {
+        let module = render(26, "Bunny \"HQ\"");
+        assert!(module.contains("    day: 26,\n    name: \"Bunny \\\"HQ\\\"\","));
+        assert!(module.contains("puzzle: &Puzzle {},"));
+        assert!(!module.contains("__"));
+    }
+}
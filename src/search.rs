@@ -0,0 +1,275 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::geometry::Int;
+
+/// How much work a search did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Nodes whose successors were generated.
+    pub expanded: usize,
+    /// The most nodes waiting in the frontier at once.
+    pub frontier_peak: usize,
+}
+
+impl Stats {
+    fn expand(&mut self, frontier: usize) {
+        self.expanded += 1;
+        self.frontier_peak = self.frontier_peak.max(frontier);
+    }
+}
+
+/// A path found by a search, from the start to the goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    /// The cheapest path to a goal, or `None` if no goal can be reached.
+    pub path: Option<Path<N, C>>,
+    pub stats: Stats,
+}
+
+/// Every node reached by a search, with the steps it takes to get there.
+#[derive(Debug, Clone)]
+pub struct Reached<N> {
+    pub distances: HashMap<N, usize>,
+    pub stats: Stats,
+}
+
+/// The nodes seen by a search, each with the index of the node it was reached from.
+struct Tree<N, C> {
+    nodes: Vec<(N, usize, C)>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Eq + Hash + Clone, C: Int> Tree<N, C> {
+    fn new(start: N) -> Tree<N, C> {
+        Tree {
+            nodes: vec![(start.clone(), usize::MAX, C::ZERO)],
+            index: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Records that `node` can be reached from `parent` for `cost`. Returns its index if
+    /// that is cheaper than any way found before.
+    fn reach(&mut self, node: N, parent: usize, cost: C) -> Option<usize> {
+        match self.index.entry(node) {
+            Entry::Occupied(entry) => {
+                let i = *entry.get();
+                if self.nodes[i].2 <= cost {
+                    return None;
+                }
+                self.nodes[i].1 = parent;
+                self.nodes[i].2 = cost;
+                Some(i)
+            }
+            Entry::Vacant(entry) => {
+                self.nodes.push((entry.key().clone(), parent, cost));
+                entry.insert(self.nodes.len() - 1);
+                Some(self.nodes.len() - 1)
+            }
+        }
+    }
+
+    /// The path from the start to node `i`.
+    fn path(&self, mut i: usize) -> Path<N, C> {
+        let cost = self.nodes[i].2;
+        let mut nodes = vec![];
+        while i != usize::MAX {
+            nodes.push(self.nodes[i].0.clone());
+            i = self.nodes[i].1;
+        }
+        nodes.reverse();
+        Path { nodes, cost }
+    }
+}
+
+/// The path with the fewest steps from `start` to a node matching `success`.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut stats = Stats::default();
+    let mut tree = Tree::new(start);
+    let mut queue = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        if success(&tree.nodes[i].0) {
+            return Search {
+                path: Some(tree.path(i)),
+                stats,
+            };
+        }
+        stats.expand(queue.len() + 1);
+        let steps = tree.nodes[i].2 + 1;
+        for next in successors(&tree.nodes[i].0) {
+            if !tree.index.contains_key(&next) {
+                queue.extend(tree.reach(next, i, steps));
+            }
+        }
+    }
+    Search { path: None, stats }
+}
+
+/// The cheapest path from `start` to a node matching `success`, where `successors` gives
+/// the nodes next to a node with the cost of moving there.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    success: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Int,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::ZERO, success)
+}
+
+/// Like `dijkstra`, but nodes closer to a goal by `heuristic` are tried first. The result is
+/// only the cheapest path if `heuristic` never overestimates the cost left.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut success: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Int,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut stats = Stats::default();
+    let mut frontier = BinaryHeap::from([Reverse((heuristic(&start), Reverse(C::ZERO), 0))]);
+    let mut tree = Tree::new(start);
+    // Of the nodes that look as close to a goal, the ones furthest from the start go first.
+    while let Some(Reverse((_, Reverse(cost), i))) = frontier.pop() {
+        if cost > tree.nodes[i].2 {
+            continue;
+        }
+        if success(&tree.nodes[i].0) {
+            return Search {
+                path: Some(tree.path(i)),
+                stats,
+            };
+        }
+        stats.expand(frontier.len() + 1);
+        for (next, step) in successors(&tree.nodes[i].0) {
+            let estimate = heuristic(&next);
+            if let Some(j) = tree.reach(next, i, cost + step) {
+                frontier.push(Reverse((cost + step + estimate, Reverse(cost + step), j)));
+            }
+        }
+    }
+    Search { path: None, stats }
+}
+
+/// Every node that can be reached from `start` in at most `limit` steps, or in any number
+/// of steps without a limit.
+pub fn reachable<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    limit: Option<usize>,
+) -> Reached<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut stats = Stats::default();
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, steps)) = queue.pop_front() {
+        if limit.is_some_and(|limit| steps >= limit) {
+            continue;
+        }
+        stats.expand(queue.len() + 1);
+        for next in successors(&node) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                queue.push_back((entry.key().clone(), steps + 1));
+                entry.insert(steps + 1);
+            }
+        }
+    }
+    Reached { distances, stats }
+}
+
+/// The most steps any path from `start` takes to a node matching `success`, which ends the
+/// path. Every path is walked, so the nodes must not lead back to a node seen before, like
+/// states that remember the moves that led to them.
+pub fn longest<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> (Option<usize>, Stats)
+where
+    I: IntoIterator<Item = N>,
+{
+    let mut stats = Stats::default();
+    let mut longest = None;
+    let mut level = vec![start];
+    let mut steps = 0;
+    while !level.is_empty() {
+        let mut next_level = vec![];
+        for node in &level {
+            if success(node) {
+                longest = Some(steps);
+                continue;
+            }
+            stats.expand(level.len() + next_level.len());
+            next_level.extend(successors(node));
+        }
+        level = next_level;
+        steps += 1;
+    }
+    (longest, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra, longest, reachable};
+
+    /// Moves along the numbers: one up for 1, or five up for 3.
+    fn moves(n: &i32) -> Vec<(i32, u32)> {
+        vec![(n + 1, 1), (n + 5, 3)]
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let found = bfs(0, |n| [n + 1, n + 5], |n| *n == 7).path.unwrap();
+        assert_eq!((found.nodes, found.cost), (vec![0, 1, 2, 7], 3));
+        let found = dijkstra(0, moves, |n| *n == 7).path.unwrap();
+        assert_eq!((found.nodes.len(), found.cost), (4, 5));
+        let search = astar(0, moves, |n| (7 - n).max(0) as u32 * 3 / 5, |n| *n == 7);
+        assert_eq!(search.path.unwrap().cost, 5);
+        assert!(search.stats.expanded > 0);
+        assert!(bfs(0, |n| [(n + 1) % 3], |n| *n == 5).path.is_none());
+    }
+
+    #[test]
+    fn reach() {
+        let reached = reachable((0, 0), |(x, y)| [(x + 1, *y), (*x, y + 1)], Some(2));
+        assert_eq!(reached.distances.len(), 6);
+        assert_eq!(reached.distances[&(1, 1)], 2);
+        assert_eq!(reached.stats.frontier_peak, 3);
+        assert_eq!(reachable(0, |n| [(n + 1) % 4], None).distances.len(), 4);
+    }
+
+    #[test]
+    fn longest_path() {
+        let (steps, _) = longest(String::new(), |s| ["a", "b"].map(|c| s.clone() + c), |s| {
+            s.ends_with("bb") || s.len() == 6
+        });
+        assert_eq!(steps, Some(6));
+    }
+}
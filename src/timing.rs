@@ -0,0 +1,23 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static PARSE_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Runs a puzzle's input parser and adds its runtime to the parse time of the
+/// current thread, so the benchmark can report it apart from solving.
+pub fn parse<T>(parser: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let parsed = parser();
+    let elapsed = start.elapsed();
+    PARSE_TIME.with(|time| time.set(time.get() + elapsed));
+    parsed
+}
+
+/// Returns the parse time collected since the last call and resets it.
+pub fn take_parse_time() -> Duration {
+    PARSE_TIME.with(|time| time.replace(Duration::ZERO))
+}
@@ -0,0 +1,267 @@
+use std::io::{self, BufWriter, Write};
+
+use crate::{
+    assembunny::{
+        self,
+        debugger::{Condition, Debugger, Stop},
+        decompiler, Computer, Instruction, Registers, REGISTER_NAMES,
+    },
+    common::{self, Config, Data},
+    day12, day23,
+    params::{self, Params},
+    registry,
+};
+
+/// Steps a trace runs unless `--limit` says otherwise, so day 25 stops by itself.
+const DEFAULT_LIMIT: u64 = 10_000_000;
+
+pub struct TraceConfig {
+    day: i32,
+    part: i32,
+    data_type: Data,
+    input: Option<String>,
+    params: Vec<(String, String)>,
+    registers: Vec<(usize, i64)>,
+    breakpoints: Vec<Condition>,
+    /// The most steps to run, `None` for no limit.
+    limit: Option<u64>,
+    histogram: bool,
+    quiet: bool,
+    optimized: bool,
+}
+
+impl TraceConfig {
+    /// Parses `<day> <part> (<data> | --input <path>) [--param key=value] [--reg r=n]
+    /// [--break condition] [--limit n] [--histogram] [--quiet] [--optimized]`.
+    /// The limit defaults to `DEFAULT_LIMIT` steps, and `--limit 0` runs until the program halts.
+    pub fn new(args: &[String]) -> Result<TraceConfig, String> {
+        if args.len() < 3 {
+            return Err(String::from("trace expects <day> <part> <data>"));
+        }
+        let (data_type, flags) = match args[2].starts_with("--") {
+            true => (Data::Real, &args[2..]),
+            false => (Data::parse(&args[2])?, &args[3..]),
+        };
+        let part = match args[1].parse::<i32>() {
+            Ok(part @ 1..=2) => part,
+            _ => return Err(String::from("Part parameter must be 1 or 2")),
+        };
+        let mut config = TraceConfig {
+            day: parse_day(&args[0])?,
+            part,
+            data_type,
+            input: None,
+            params: vec![],
+            registers: vec![],
+            breakpoints: vec![],
+            limit: Some(DEFAULT_LIMIT),
+            histogram: false,
+            quiet: false,
+            optimized: false,
+        };
+        let mut rest = flags.iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--input" => config.input = Some(rest.next().ok_or("Missing value for --input")?.clone()),
+                "--param" => {
+                    let assignment = rest.next().ok_or("Missing key=value after --param")?;
+                    config.params.push(params::parse_assignment(assignment)?);
+                }
+                "--reg" => {
+                    let assignment = rest.next().ok_or("Missing register=value after --reg")?;
+                    let (name, value) = params::parse_assignment(assignment)?;
+                    let register = REGISTER_NAMES
+                        .iter()
+                        .position(|register| name == register.to_string())
+                        .ok_or_else(|| format!("Unknown register '{}', expected a-d", name))?;
+                    let value = value
+                        .parse::<i64>()
+                        .map_err(|_| format!("Register value must be an integer, got '{}'", value))?;
+                    config.registers.push((register, value));
+                }
+                "--break" => {
+                    let condition = rest.next().ok_or("Missing condition after --break")?;
+                    config.breakpoints.push(Condition::parse(condition)?);
+                }
+                "--limit" => {
+                    let value = rest.next().ok_or("Missing value for --limit")?;
+                    config.limit = match value.parse::<u64>() {
+                        Ok(0) => None,
+                        Ok(limit) => Some(limit),
+                        Err(_) => {
+                            return Err(format!(
+                                "--limit must be a non-negative integer (0 for no limit), got {}",
+                                value
+                            ))
+                        }
+                    };
+                }
+                "--histogram" => config.histogram = true,
+                "--quiet" => config.quiet = true,
+                "--optimized" => config.optimized = true,
+                other => return Err(format!("Unexpected argument: {}", other)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// The registers of the puzzle part, with the `--reg` overrides applied.
+    fn initial_registers(&self) -> Result<Registers, String> {
+        let puzzle = registry::find(self.day).ok_or_else(|| format!("Day {} is not registered", self.day))?;
+        let params = Params::resolve(&puzzle.puzzle.params(), self.part, &self.params)?;
+        let mut registers = match self.day {
+            12 => day12::registers(&params),
+            23 => day23::registers(&params),
+            _ => [0; 4],
+        };
+        for (register, value) in &self.registers {
+            registers[*register] = *value;
+        }
+        Ok(registers)
+    }
+}
+
+impl Config for TraceConfig {
+    fn get_day(&self) -> i32 {
+        self.day
+    }
+
+    fn get_part(&self) -> i32 {
+        self.part
+    }
+
+    fn get_data_type(&self) -> Data {
+        self.data_type
+    }
+
+    fn get_input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+}
+
+pub struct DecompileConfig {
+    day: i32,
+    data_type: Data,
+    input: Option<String>,
+}
+
+impl DecompileConfig {
+    /// Parses `<day> <data>` or `<day> --input <path>`.
+    pub fn new(args: &[String]) -> Result<DecompileConfig, String> {
+        match args {
+            [day, flag, path] if flag == "--input" => Ok(DecompileConfig {
+                day: parse_day(day)?,
+                data_type: Data::Real,
+                input: Some(path.clone()),
+            }),
+            [day, data] => Ok(DecompileConfig {
+                day: parse_day(day)?,
+                data_type: Data::parse(data)?,
+                input: None,
+            }),
+            _ => Err(String::from("decompile expects <day> <data> or <day> --input <path>")),
+        }
+    }
+}
+
+impl Config for DecompileConfig {
+    fn get_day(&self) -> i32 {
+        self.day
+    }
+
+    fn get_part(&self) -> i32 {
+        1
+    }
+
+    fn get_data_type(&self) -> Data {
+        self.data_type
+    }
+
+    fn get_input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+}
+
+fn parse_day(arg: &str) -> Result<i32, String> {
+    match arg.parse::<i32>() {
+        Ok(day @ (12 | 23 | 25)) => Ok(day),
+        _ => Err(String::from("Only days 12, 23 and 25 run assembunny programs")),
+    }
+}
+
+fn read_program(config: &impl Config) -> Result<Vec<Instruction>, String> {
+    let input = common::read_input(config).map_err(|err| format!("Cannot read input file: {}", err))?;
+    assembunny::parse_program(input.text()).map_err(|err| err.in_day(config.get_day()).to_string())
+}
+
+/// Prints the program of the configured puzzle as pseudocode.
+pub fn decompile(config: &DecompileConfig) -> Result<(), String> {
+    print!("{}", decompiler::decompile(&read_program(config)?));
+    Ok(())
+}
+
+/// Runs the program of the configured puzzle under the debugger, printing every
+/// step unless `--quiet` is given, then why it stopped and the final state.
+pub fn run(config: &TraceConfig) -> Result<(), String> {
+    let mut computer = Computer::new(read_program(config)?, config.initial_registers()?);
+    if config.optimized {
+        computer = computer.optimized();
+    }
+    let mut debugger = Debugger::new(computer, config.breakpoints.clone());
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut failed = None;
+    let stop = debugger.run(config.limit, |step| {
+        if !config.quiet && failed.is_none() {
+            failed = writeln!(out, "{}", step).err();
+        }
+    });
+    if let Some(err) = failed {
+        return Err(format!("Cannot write trace: {}", err));
+    }
+    print_summary(&mut out, &debugger, stop).map_err(|err| format!("Cannot write trace: {}", err))?;
+    if config.histogram {
+        print_histogram(&mut out, &debugger).map_err(|err| format!("Cannot write trace: {}", err))?;
+    }
+    out.flush().map_err(|err| format!("Cannot write trace: {}", err))
+}
+
+fn print_summary(out: &mut impl Write, debugger: &Debugger, stop: Stop) -> io::Result<()> {
+    let computer = &debugger.computer;
+    match stop {
+        Stop::Halted => writeln!(out, "Halted after {} steps", debugger.steps)?,
+        Stop::Breakpoint(condition) => writeln!(
+            out,
+            "Breakpoint {} hit after {} steps, next instruction at {}",
+            condition, debugger.steps, computer.pc
+        )?,
+        Stop::Limit => writeln!(out, "Stopped at the limit of {} steps", debugger.steps)?,
+    }
+    let registers = REGISTER_NAMES
+        .iter()
+        .zip(computer.registers)
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<String>>();
+    writeln!(out, "Registers: {}", registers.join(" "))?;
+    if !computer.output.is_empty() {
+        writeln!(out, "Output: {} values, last {:?}", computer.output.len(), computer.output.last().unwrap())?;
+    }
+    Ok(())
+}
+
+/// Prints how often every instruction ran, in program order so hot loops show up as blocks.
+fn print_histogram(out: &mut impl Write, debugger: &Debugger) -> io::Result<()> {
+    let total = debugger.steps.max(1) as f64;
+    writeln!(out, "{:>4} | {:>12} | {:>7} | Instruction", "pc", "Count", "Share")?;
+    writeln!(out, "-----+--------------+---------+------------")?;
+    for (pc, (count, instruction)) in debugger.histogram.iter().zip(&debugger.computer.program).enumerate() {
+        writeln!(
+            out,
+            "{:>4} | {:>12} | {:>6.2}% | {}",
+            pc,
+            count,
+            *count as f64 * 100.0 / total,
+            instruction
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use std::{
+    collections::BTreeMap,
+    fs, io, panic,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    common, registry,
+    runner::{self, JobConfig, PARTS},
+};
+
+/// The answer store, kept in the inputs root next to the data it was recorded for.
+const DEFAULT_ANSWERS: &str = "answers.tsv";
+
+pub struct VerifyConfig {
+    days: Vec<i32>,
+    parts: Vec<i32>,
+    pub record: bool,
+    answers: PathBuf,
+}
+
+impl VerifyConfig {
+    /// Parses `[<day> [<part>]] [--record] [--answers <file>]`.
+    pub fn new(args: &[String]) -> Result<VerifyConfig, String> {
+        let mut positional: Vec<i32> = vec![];
+        let mut record = false;
+        let mut answers = common::inputs_root().join(DEFAULT_ANSWERS);
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--record" => record = true,
+                "--answers" => {
+                    answers = PathBuf::from(args.next().ok_or("Missing value for --answers")?)
+                }
+                value => match value.parse::<i32>() {
+                    Ok(number) => positional.push(number),
+                    Err(e) => return Err(format!("Cannot parse {} to i32: {}", value, e)),
+                },
+            }
+        }
+        if positional.len() > 2 {
+            return Err(String::from("Too many arguments for verify"));
+        }
+        let days = match positional.first() {
+            Some(day) if registry::find(*day).is_some() => vec![*day],
+            Some(day) => return Err(format!("Day {} is not registered", day)),
+            None => registry::days().collect(),
+        };
+        let parts = match positional.get(1) {
+            Some(part) if PARTS.contains(part) => vec![*part],
+            Some(_) => return Err(String::from("Part parameter must be 1 or 2")),
+            None => PARTS.collect(),
+        };
+        Ok(VerifyConfig {
+            days,
+            parts,
+            record,
+            answers,
+        })
+    }
+}
+
+type Key = (i32, i32, String);
+
+/// Accepted answers, keyed by day, part and the MD5 hash of the input they were
+/// given for. Stored as one tab separated `day part hash answer` line per answer.
+#[derive(Debug, Default, PartialEq)]
+pub struct AnswerStore {
+    answers: BTreeMap<Key, String>,
+}
+
+impl AnswerStore {
+    pub fn parse(text: &str) -> Result<AnswerStore, String> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            let [day, part, hash, answer] = fields[..] else {
+                return Err(format!("line {}: expected day, part, hash and answer separated by tabs", i + 1));
+            };
+            let number = |field: &str| {
+                field
+                    .parse::<i32>()
+                    .map_err(|_| format!("line {}: expected a number, got '{}'", i + 1, field))
+            };
+            answers.insert((number(day)?, number(part)?, hash.to_string()), unescape(answer));
+        }
+        Ok(AnswerStore { answers })
+    }
+
+    /// Reads the store. A file that does not exist yet is an empty store only if
+    /// `create` is set, as it is when recording; otherwise every answer would pass as new.
+    pub fn load(path: &Path, create: bool) -> Result<AnswerStore, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => AnswerStore::parse(&text).map_err(|err| format!("{}, {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound && create => Ok(AnswerStore::default()),
+            Err(err) => Err(format!("Cannot read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|err| format!("Cannot write {}: {}", path.display(), err))
+    }
+
+    pub fn get(&self, day: i32, part: i32, hash: &str) -> Option<&String> {
+        self.answers.get(&(day, part, hash.to_string()))
+    }
+
+    pub fn insert(&mut self, day: i32, part: i32, hash: &str, answer: &str) {
+        self.answers.insert((day, part, hash.to_string()), answer.to_string());
+    }
+}
+
+impl std::fmt::Display for AnswerStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for ((day, part, hash), answer) in &self.answers {
+            writeln!(f, "{}\t{}\t{}\t{}", day, part, hash, escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut result = String::new();
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+pub fn input_hash(input: &str) -> String {
+    format!("{:x}", md5::compute(input))
+}
+
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    New,
+    Error(String),
+}
+
+pub struct Verdict {
+    day: i32,
+    part: i32,
+    answer: Option<String>,
+    status: Status,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self.status, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+/// Solves every selected (day, part) on the real data and compares the answers
+/// with the store. With `--record`, every answer that was given is accepted
+/// into the store and the store is saved.
+pub fn run(config: &VerifyConfig) -> Result<Vec<Verdict>, String> {
+    let mut store = AnswerStore::load(&config.answers, config.record)?;
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut verdicts = vec![];
+    for day in &config.days {
+        for part in &config.parts {
+            verdicts.push(verify(&mut store, *day, *part, config.record));
+        }
+    }
+    panic::set_hook(default_hook);
+    if config.record {
+        store.save(&config.answers)?;
+    }
+    Ok(verdicts)
+}
+
+fn verify(store: &mut AnswerStore, day: i32, part: i32, record: bool) -> Verdict {
+    let input = match common::read_input(&JobConfig { day, part }) {
+        Ok(input) => input,
+        Err(err) => {
+            return Verdict {
+                day,
+                part,
+                answer: None,
+                status: Status::Error(format!("cannot read input file: {}", err)),
+            }
+        }
+    };
+    let hash = input_hash(input.text());
+    let answer = match runner::solve(day, part, &input, &[]) {
+        Ok(answer) => answer.to_string(),
+        Err(err) => {
+            return Verdict {
+                day,
+                part,
+                answer: None,
+                status: Status::Error(err),
+            }
+        }
+    };
+    let status = match store.get(day, part, &hash) {
+        Some(expected) if *expected == answer => Status::Pass,
+        Some(expected) => Status::Fail {
+            expected: expected.clone(),
+        },
+        None => Status::New,
+    };
+    if record {
+        store.insert(day, part, &hash, &answer);
+    }
+    Verdict {
+        day,
+        part,
+        answer: Some(answer),
+        status,
+    }
+}
+
+pub fn print_table(verdicts: &[Verdict], record: bool) {
+    println!("{:>3} | {:>4} | {:<6} | Answer", "Day", "Part", "Status");
+    println!("----+------+--------+-------");
+    for verdict in verdicts {
+        let answer = verdict.answer.as_deref().map(escape).unwrap_or_default();
+        let (status, detail) = match &verdict.status {
+            Status::Pass => ("PASS", answer),
+            Status::Fail { expected } => ("FAIL", format!("{} (expected {})", answer, escape(expected))),
+            Status::New => ("NEW", answer),
+            Status::Error(err) => ("FAIL", format!("error: {}", err)),
+        };
+        println!("{:>3} | {:>4} | {:<6} | {}", verdict.day, verdict.part, status, detail);
+    }
+    let count = |wanted: fn(&Status) -> bool| verdicts.iter().filter(|verdict| wanted(&verdict.status)).count();
+    println!(
+        "{} passed, {} failed, {} new{}",
+        count(|status| matches!(status, Status::Pass)),
+        count(|status| matches!(status, Status::Fail { .. } | Status::Error(_))),
+        count(|status| matches!(status, Status::New)),
+        if record { ", answers recorded" } else { "" }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::AnswerStore;
+
+    #[test]
+    fn store_round_trip() {
+        let mut store = AnswerStore::default();
+        store.insert(8, 2, "abc", "#..#\n#\t\\");
+        store.insert(1, 1, "def", "209");
+        let text = store.to_string();
+        assert_eq!(text, "1\t1\tdef\t209\n8\t2\tabc\t#..#\\n#\\t\\\\\n");
+        let parsed = AnswerStore::parse(&text).unwrap();
+        assert_eq!(parsed, store);
+        assert_eq!(parsed.get(8, 2, "abc").map(String::as_str), Some("#..#\n#\t\\"));
+        assert!(AnswerStore::parse("1\t1\tdef").is_err());
+    }
+
+    #[test]
+    fn missing_store() {
+        let path = Path::new("inputs/no-such-answers.tsv");
+        assert!(AnswerStore::load(path, false).is_err());
+        assert_eq!(AnswerStore::load(path, true), Ok(AnswerStore::default()));
+    }
+}